@@ -67,6 +67,10 @@ mkdir -p "$AHOY_BIN"
 # Copy binary
 cp target/release/ahoy "$AHOY_BIN/ahoy"
 
+# Copy the default notification icon
+mkdir -p "$AHOY_HOME/icons"
+cp resources/icons/ahoy-icon-128.png "$AHOY_HOME/icons/"
+
 # Copy Ahoy.app bundle (contains Swift helper, icons, Info.plist)
 rm -rf "$AHOY_APP"
 cp -R Ahoy.app "$AHOY_APP"
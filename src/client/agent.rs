@@ -0,0 +1,35 @@
+use clap::ValueEnum;
+
+/// LLM coding agents ahoy knows how to style notifications for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Agent {
+    Claude,
+    Codex,
+    Gemini,
+    Cursor,
+    Aider,
+}
+
+impl Agent {
+    /// Default notification title for this agent
+    pub fn title(&self) -> &'static str {
+        match self {
+            Agent::Claude => "Claude Code",
+            Agent::Codex => "Codex",
+            Agent::Gemini => "Gemini CLI",
+            Agent::Cursor => "Cursor",
+            Agent::Aider => "Aider",
+        }
+    }
+
+    /// Name as passed to --agent, recorded in the notification's metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            Agent::Claude => "claude",
+            Agent::Codex => "codex",
+            Agent::Gemini => "gemini",
+            Agent::Cursor => "cursor",
+            Agent::Aider => "aider",
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Milliseconds since the Unix epoch
+pub fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+}
+
+/// Whether a notification at `now_ms` falls within the cooldown of the last one shown.
+/// A last time in the future (the clock was set back) counts as expired, so a
+/// clock change can't silence notifications until the clock catches up.
+pub fn within_cooldown(last_shown_ms: Option<u128>, now_ms: u128, cooldown_ms: u64) -> bool {
+    last_shown_ms.is_some_and(|last| last <= now_ms && now_ms - last < u128::from(cooldown_ms))
+}
+
+/// Read the time the last notification was shown, if recorded
+pub fn last_shown(path: &Path) -> Option<u128> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Record that a notification was shown at `now_ms`
+pub fn record_shown(path: &Path, now_ms: u128) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, now_ms.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_within_cooldown_no_previous() {
+        assert!(!within_cooldown(None, 1_000, 500));
+    }
+
+    #[test]
+    fn test_within_cooldown_too_soon() {
+        assert!(within_cooldown(Some(1_000), 1_499, 500));
+    }
+
+    #[test]
+    fn test_within_cooldown_elapsed() {
+        assert!(!within_cooldown(Some(1_000), 1_500, 500));
+        assert!(!within_cooldown(Some(1_000), 5_000, 500));
+    }
+
+    #[test]
+    fn test_within_cooldown_clock_went_backwards() {
+        assert!(!within_cooldown(Some(2_000), 1_000, 500));
+        assert!(!within_cooldown(Some(1_001), 1_000, 60_000));
+    }
+
+    #[test]
+    fn test_record_and_read_last_shown() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state").join("last_notification");
+
+        assert_eq!(last_shown(&path), None);
+        record_shown(&path, 1_234).unwrap();
+        assert_eq!(last_shown(&path), Some(1_234));
+    }
+
+    #[test]
+    fn test_cooldown_timing_with_real_clock() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("last_notification");
+
+        record_shown(&path, now_ms()).unwrap();
+        assert!(within_cooldown(last_shown(&path), now_ms(), 60_000));
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(!within_cooldown(last_shown(&path), now_ms(), 10));
+    }
+}
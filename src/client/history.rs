@@ -0,0 +1,299 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::client::message::Notification;
+use crate::config::{self, Config};
+
+/// The history file is rotated to `.1` once it would grow past this size by default
+pub const DEFAULT_HISTORY_MAX_BYTES: u64 = 1024 * 1024;
+
+/// A delivered notification, one JSON line per entry in the history file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    #[serde(serialize_with = "serialize_millis")]
+    pub timestamp: DateTime<Local>,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+/// Write a timestamp as RFC 3339 with millisecond precision
+fn serialize_millis<S: Serializer>(timestamp: &DateTime<Local>, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&timestamp.to_rfc3339_opts(SecondsFormat::Millis, false))
+}
+
+/// Where the previous history file goes when the current one is rotated
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Append an entry, first rotating the file to `.1` if the entry would take
+/// it past `max_bytes`. Only one rotated file is kept. Zero disables rotation.
+pub fn append(path: &Path, entry: &Entry, max_bytes: u64) -> Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let size = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    if max_bytes > 0 && size > 0 && size + line.len() as u64 > max_bytes {
+        fs::rename(path, rotated_path(path))
+            .with_context(|| format!("Failed to rotate {}", path.display()))?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read entries from one file, skipping lines that don't parse
+fn read_file(path: &Path) -> Result<Vec<Entry>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// The most recent `limit` entries at or after `since`, oldest first,
+/// including ones already rotated to `.1`
+pub fn read(path: &Path, limit: usize, since: Option<DateTime<Local>>) -> Result<Vec<Entry>> {
+    let mut entries = read_file(&rotated_path(path))?;
+    entries.extend(read_file(path)?);
+
+    if let Some(since) = since {
+        entries.retain(|entry| entry.timestamp >= since);
+    }
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+/// Record a delivered notification in the history file
+pub fn record(notification: &Notification, config: &Config) -> Result<()> {
+    let entry = Entry {
+        timestamp: Local::now(),
+        notification: notification.clone(),
+    };
+    let max_bytes = config
+        .history_max_bytes
+        .unwrap_or(DEFAULT_HISTORY_MAX_BYTES);
+    append(&config::history_path(), &entry, max_bytes)
+}
+
+/// Parse a `--since` age like "90s", "30m", "2h" or "7d"
+pub fn parse_age(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{}', expected e.g. 30m, 2h or 7d", s))?;
+
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("invalid age '{}', expected e.g. 30m, 2h or 7d", s)),
+    };
+    Ok(Duration::from_secs(number * seconds))
+}
+
+/// One line per entry: local time to the millisecond, title and body
+pub fn render(entry: &Entry) -> String {
+    format!(
+        "{}  {}: {}",
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+        entry.notification.title,
+        entry.notification.body
+    )
+}
+
+/// Print recent history, as JSON lines with `json`
+pub fn run(limit: usize, since: Option<Duration>, json: bool) -> Result<()> {
+    let since = since
+        .map(|age| chrono::Duration::from_std(age).map(|age| Local::now() - age))
+        .transpose()
+        .context("--since is too far back")?;
+    let entries = read(&config::history_path(), limit, since)?;
+
+    if entries.is_empty() && !json {
+        println!("No notifications in history");
+    }
+    for entry in &entries {
+        if json {
+            println!("{}", serde_json::to_string(entry)?);
+        } else {
+            println!("{}", render(entry));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn entry(minute: u32, body: &str) -> Entry {
+        Entry {
+            timestamp: Local.with_ymd_and_hms(2026, 3, 1, 9, minute, 0).unwrap(),
+            notification: Notification::new("Claude Code", body),
+        }
+    }
+
+    fn bodies(entries: &[Entry]) -> Vec<&str> {
+        entries
+            .iter()
+            .map(|e| e.notification.body.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_append_and_read_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let mut first = entry(0, "[app] Task finished");
+        first.notification.url = Some("https://example.com".to_string());
+
+        append(&path, &first, 0).unwrap();
+        append(&path, &entry(1, "[app] Needs permission"), 0).unwrap();
+
+        let entries = read(&path, 10, None).unwrap();
+        assert_eq!(
+            bodies(&entries),
+            ["[app] Task finished", "[app] Needs permission"]
+        );
+        assert_eq!(entries[0].timestamp, first.timestamp);
+        assert_eq!(
+            entries[0].notification.url.as_deref(),
+            Some("https://example.com")
+        );
+    }
+
+    #[test]
+    fn test_timestamps_have_milliseconds() {
+        let mut entry = entry(0, "n0");
+        entry.timestamp += chrono::Duration::microseconds(123_456);
+
+        let json: serde_json::Value = serde_json::to_value(&entry).unwrap();
+        let timestamp = json["timestamp"].as_str().unwrap();
+        assert!(
+            timestamp.starts_with("2026-03-01T09:00:00.123"),
+            "{}",
+            timestamp
+        );
+        assert_eq!(timestamp.len(), "2026-03-01T09:00:00.123+00:00".len());
+        assert!(render(&entry).starts_with("2026-03-01 09:00:00.123  "));
+    }
+
+    #[test]
+    fn test_read_missing_file_is_empty() {
+        let dir = TempDir::new().unwrap();
+
+        assert!(
+            read(&dir.path().join("history.jsonl"), 10, None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_read_limit_and_since() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        for minute in 0..5 {
+            append(&path, &entry(minute, &format!("n{}", minute)), 0).unwrap();
+        }
+
+        assert_eq!(bodies(&read(&path, 2, None).unwrap()), ["n3", "n4"]);
+
+        let since = Local.with_ymd_and_hms(2026, 3, 1, 9, 2, 0).unwrap();
+        assert_eq!(
+            bodies(&read(&path, 10, Some(since)).unwrap()),
+            ["n2", "n3", "n4"]
+        );
+    }
+
+    #[test]
+    fn test_rotation_at_size_threshold() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let line_len = serde_json::to_string(&entry(0, "n0")).unwrap().len() as u64 + 1;
+        let max_bytes = line_len * 2;
+
+        append(&path, &entry(0, "n0"), max_bytes).unwrap();
+        append(&path, &entry(1, "n1"), max_bytes).unwrap();
+        assert!(!rotated_path(&path).exists());
+        assert_eq!(fs::metadata(&path).unwrap().len(), max_bytes);
+
+        // The third entry would pass the cap, so the first two move to .1
+        append(&path, &entry(2, "n2"), max_bytes).unwrap();
+        assert_eq!(
+            bodies(&read_file(&rotated_path(&path)).unwrap()),
+            ["n0", "n1"]
+        );
+        assert_eq!(bodies(&read_file(&path).unwrap()), ["n2"]);
+
+        // Reading spans both files; a second rotation replaces the old .1
+        assert_eq!(bodies(&read(&path, 10, None).unwrap()), ["n0", "n1", "n2"]);
+        append(&path, &entry(3, "n3"), max_bytes).unwrap();
+        append(&path, &entry(4, "n4"), max_bytes).unwrap();
+        assert_eq!(bodies(&read(&path, 10, None).unwrap()), ["n2", "n3", "n4"]);
+    }
+
+    #[test]
+    fn test_read_skips_corrupt_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        append(&path, &entry(0, "n0"), 0).unwrap();
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"truncated\n")
+            .unwrap();
+        append(&path, &entry(1, "n1"), 0).unwrap();
+
+        assert_eq!(bodies(&read(&path, 10, None).unwrap()), ["n0", "n1"]);
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_age("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_age("2h"), Ok(Duration::from_secs(2 * 3600)));
+        assert_eq!(parse_age("7d"), Ok(Duration::from_secs(7 * 86400)));
+        assert!(parse_age("2 weeks").is_err());
+        assert!(parse_age("h").is_err());
+        assert!(parse_age("10").is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render(&entry(5, "[app] Task finished")),
+            "2026-03-01 09:05:00.000  Claude Code: [app] Task finished"
+        );
+    }
+}
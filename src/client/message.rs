@@ -1,5 +1,107 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Most keys accepted in `Notification.metadata`, unless `max_metadata_keys` is configured
+pub const DEFAULT_MAX_METADATA_KEYS: usize = 32;
+
+/// Largest serialized `Notification.metadata` accepted in bytes, unless
+/// `max_metadata_bytes` is configured
+pub const DEFAULT_MAX_METADATA_BYTES: usize = 8 * 1024;
+
+/// Sound name that turns notification sound off
+pub const SILENT_SOUND: &str = "none";
+
+/// How urgently a notification should demand attention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    /// Map a command's exit code to an urgency: success is normal, failure is critical
+    pub fn from_exit_code(code: i32) -> Self {
+        if code == 0 {
+            Urgency::Normal
+        } else {
+            Urgency::Critical
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        }
+    }
+}
+
+impl FromStr for Urgency {
+    type Err = String;
+
+    /// Parse an urgency, accepting the vocabularies used by other notifiers:
+    /// low/normal/critical, ntfy's min/low/default/high/max/urgent, and
+    /// Pushover-style numeric priorities from -2 to 2.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "low" | "min" | "-2" | "-1" => Ok(Urgency::Low),
+            "normal" | "default" | "medium" | "0" => Ok(Urgency::Normal),
+            "critical" | "high" | "max" | "urgent" | "1" | "2" => Ok(Urgency::Critical),
+            other => Err(format!(
+                "unknown urgency '{}' (expected low, normal, critical, min, default, high, max, urgent, or -2..2)",
+                other
+            )),
+        }
+    }
+}
+
+/// A button on a notification, optionally running a shell command when chosen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationAction {
+    /// Identifier reported back when the action is chosen
+    pub id: String,
+
+    /// Button label
+    pub label: String,
+
+    /// Shell command to run when the action is chosen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl FromStr for NotificationAction {
+    type Err = String;
+
+    /// Parse `ID:LABEL[:COMMAND]`. The command may itself contain colons.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let id = parts.next().unwrap_or_default().trim();
+        let label = parts.next().unwrap_or_default().trim();
+        if id.is_empty() || label.is_empty() {
+            return Err(format!(
+                "invalid action '{}' (expected ID:LABEL[:COMMAND])",
+                s
+            ));
+        }
+        let command = parts
+            .next()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from);
+
+        Ok(NotificationAction {
+            id: id.to_string(),
+            label: label.to_string(),
+            command,
+        })
+    }
+}
 
 /// A notification message sent to the daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,13 +112,53 @@ pub struct Notification {
     /// Notification body text
     pub body: String,
 
+    /// Second line shown between the title and body where the platform supports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+
     /// Optional icon identifier (e.g., "claude", "codex", "gemini")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
 
-    /// Bundle ID to activate when notification is clicked
+    /// Bundle IDs to try activating, in order, when notification is clicked.
+    /// The first one that's running wins. Accepts a single string in JSON.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_activate"
+    )]
+    pub activate: Vec<String>,
+
+    /// Notification urgency (defaults to normal when unset)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub activate: Option<String>,
+    pub urgency: Option<Urgency>,
+
+    /// URL to open when notification is clicked (takes precedence over `activate`).
+    /// Also accepted as `open_url` in JSON.
+    #[serde(alias = "open_url", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Named system sound to play, or "none" for a silent notification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
+
+    /// Path to a custom sound file to play with the notification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sound_file: Option<String>,
+
+    /// How long the notification stays on screen in milliseconds; 0 keeps it
+    /// until dismissed. Unset uses the platform default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u32>,
+
+    /// Notifications with the same key replace each other instead of stacking,
+    /// e.g. successive progress updates from one task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace_id: Option<String>,
+
+    /// Buttons to show on the notification, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<NotificationAction>,
 
     /// Optional metadata for extensibility
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
@@ -28,23 +170,148 @@ impl Notification {
         Self {
             title: title.into(),
             body: body.into(),
+            subtitle: None,
             icon: None,
-            activate: None,
+            activate: Vec::new(),
+            urgency: None,
+            url: None,
+            sound: None,
+            sound_file: None,
+            timeout_ms: None,
+            replace_id: None,
+            actions: Vec::new(),
             metadata: HashMap::new(),
         }
     }
 
+    #[allow(dead_code)]
+    pub fn with_subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    /// Reject metadata with more than `max_keys` keys or more than `max_bytes`
+    /// bytes of JSON, so oversized payloads aren't carried to every backend
+    pub fn check_metadata(&self, max_keys: usize, max_bytes: usize) -> anyhow::Result<()> {
+        if self.metadata.len() > max_keys {
+            anyhow::bail!(
+                "metadata has {} keys (max {})",
+                self.metadata.len(),
+                max_keys
+            );
+        }
+
+        let size = serde_json::to_vec(&self.metadata)?.len();
+        if size > max_bytes {
+            anyhow::bail!("metadata is {} bytes (max {})", size, max_bytes);
+        }
+        Ok(())
+    }
+
+    /// The body with the subtitle on its own line above it, for destinations
+    /// that have no subtitle field
+    pub fn body_with_subtitle(&self) -> Cow<'_, str> {
+        match &self.subtitle {
+            Some(subtitle) => Cow::Owned(format!("{}\n{}", subtitle, self.body)),
+            None => Cow::Borrowed(&self.body),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
         self.icon = Some(icon.into());
         self
     }
 
+    /// Add a bundle ID to the activation fallback list
     #[allow(dead_code)]
     pub fn with_activate(mut self, bundle_id: impl Into<String>) -> Self {
-        self.activate = Some(bundle_id.into());
+        self.activate.push(bundle_id.into());
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_urgency(mut self, urgency: Urgency) -> Self {
+        self.urgency = Some(urgency);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_sound(mut self, sound: impl Into<String>) -> Self {
+        self.sound = Some(sound.into());
+        self
+    }
+
+    /// Whether sound was turned off with `sound: "none"`
+    pub fn is_silent(&self) -> bool {
+        self.sound
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case(SILENT_SOUND))
+    }
+
+    #[allow(dead_code)]
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// The command to run for the action the user chose, if it has one
+    pub fn action_command(&self, id: &str) -> Option<&str> {
+        self.actions
+            .iter()
+            .find(|action| action.id == id)
+            .and_then(|action| action.command.as_deref())
+    }
+
+    #[allow(dead_code)]
+    pub fn with_sound_file(mut self, path: impl Into<String>) -> Self {
+        self.sound_file = Some(path.into());
         self
     }
+
+    #[allow(dead_code)]
+    pub fn with_timeout_ms(mut self, timeout_ms: u32) -> Self {
+        self.timeout_ms = Some(timeout_ms);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_replace_id(mut self, key: impl Into<String>) -> Self {
+        self.replace_id = Some(key.into());
+        self
+    }
+
+    /// Whether the notification should stay until dismissed (`timeout_ms: 0`)
+    pub fn is_sticky(&self) -> bool {
+        self.timeout_ms == Some(0)
+    }
+}
+
+/// Deserialize `activate` from either a single bundle ID or a list of them
+fn deserialize_activate<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let ids = match Option::<OneOrMany>::deserialize(deserializer)? {
+        Some(OneOrMany::One(id)) => vec![id],
+        Some(OneOrMany::Many(ids)) => ids,
+        None => Vec::new(),
+    };
+    // An empty bundle ID can't be activated, so drop it
+    Ok(ids.into_iter().filter(|id| !id.is_empty()).collect())
 }
 
 #[cfg(test)]
@@ -57,7 +324,7 @@ mod tests {
         assert_eq!(notif.title, "Title");
         assert_eq!(notif.body, "Body");
         assert!(notif.icon.is_none());
-        assert!(notif.activate.is_none());
+        assert!(notif.activate.is_empty());
         assert!(notif.metadata.is_empty());
     }
 
@@ -70,7 +337,224 @@ mod tests {
     #[test]
     fn test_notification_with_activate() {
         let notif = Notification::new("Title", "Body").with_activate("com.apple.Terminal");
-        assert_eq!(notif.activate, Some("com.apple.Terminal".to_string()));
+        assert_eq!(notif.activate, vec!["com.apple.Terminal".to_string()]);
+    }
+
+    #[test]
+    fn test_notification_with_activate_fallbacks() {
+        let notif = Notification::new("Title", "Body")
+            .with_activate("com.mitchellh.ghostty")
+            .with_activate("com.apple.Terminal");
+        assert_eq!(
+            notif.activate,
+            vec![
+                "com.mitchellh.ghostty".to_string(),
+                "com.apple.Terminal".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notification_with_urgency() {
+        let notif = Notification::new("Title", "Body").with_urgency(Urgency::Critical);
+        assert_eq!(notif.urgency, Some(Urgency::Critical));
+    }
+
+    #[test]
+    fn test_urgency_from_exit_code() {
+        assert_eq!(Urgency::from_exit_code(0), Urgency::Normal);
+        assert_eq!(Urgency::from_exit_code(1), Urgency::Critical);
+        assert_eq!(Urgency::from_exit_code(-1), Urgency::Critical);
+    }
+
+    #[test]
+    fn test_urgency_from_str_aliases() {
+        for (input, expected) in [
+            ("low", Urgency::Low),
+            ("min", Urgency::Low),
+            ("-2", Urgency::Low),
+            ("-1", Urgency::Low),
+            ("normal", Urgency::Normal),
+            ("default", Urgency::Normal),
+            ("0", Urgency::Normal),
+            ("critical", Urgency::Critical),
+            ("HIGH", Urgency::Critical),
+            ("max", Urgency::Critical),
+            ("urgent", Urgency::Critical),
+            ("1", Urgency::Critical),
+            ("2", Urgency::Critical),
+        ] {
+            assert_eq!(input.parse::<Urgency>(), Ok(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_urgency_from_str_unknown() {
+        let err = "loud".parse::<Urgency>().unwrap_err();
+        assert!(err.contains("loud"));
+        assert!(err.contains("critical"));
+        assert!("3".parse::<Urgency>().is_err());
+    }
+
+    #[test]
+    fn test_urgency_serialization() {
+        let notif = Notification::new("Test", "Message").with_urgency(Urgency::Critical);
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""urgency":"critical""#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.urgency, Some(Urgency::Critical));
+    }
+
+    #[test]
+    fn test_notification_with_url() {
+        let notif = Notification::new("Title", "Body").with_url("https://example.com/pr/1");
+        assert_eq!(notif.url, Some("https://example.com/pr/1".to_string()));
+    }
+
+    #[test]
+    fn test_notification_with_sound_file() {
+        let notif = Notification::new("Title", "Body").with_sound_file("/tmp/ding.aiff");
+        assert_eq!(notif.sound_file, Some("/tmp/ding.aiff".to_string()));
+    }
+
+    #[test]
+    fn test_notification_sound_serialization() {
+        let notif = Notification::new("Title", "Body").with_sound("Ping");
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""sound":"Ping""#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.sound.as_deref(), Some("Ping"));
+        assert!(!parsed.is_silent());
+    }
+
+    #[test]
+    fn test_notification_sound_none_is_silent() {
+        assert!(
+            Notification::new("Title", "Body")
+                .with_sound("none")
+                .is_silent()
+        );
+        assert!(
+            Notification::new("Title", "Body")
+                .with_sound("None")
+                .is_silent()
+        );
+        assert!(!Notification::new("Title", "Body").is_silent());
+    }
+
+    #[test]
+    fn test_notification_timeout_serialization() {
+        let notif = Notification::new("Title", "Body").with_timeout_ms(5000);
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""timeout_ms":5000"#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.timeout_ms, Some(5000));
+        assert!(!parsed.is_sticky());
+
+        let json = serde_json::to_string(&Notification::new("Title", "Body")).unwrap();
+        assert!(!json.contains("timeout_ms"));
+    }
+
+    #[test]
+    fn test_notification_zero_timeout_is_sticky() {
+        let notif = Notification::new("Title", "Body").with_timeout_ms(0);
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""timeout_ms":0"#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert!(parsed.is_sticky());
+        assert!(!Notification::new("Title", "Body").is_sticky());
+    }
+
+    #[test]
+    fn test_notification_subtitle_serialization() {
+        let notif = Notification::new("Claude Code", "Task finished").with_subtitle("myapp");
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""subtitle":"myapp""#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.subtitle.as_deref(), Some("myapp"));
+
+        let json = serde_json::to_string(&Notification::new("Title", "Body")).unwrap();
+        assert!(!json.contains("subtitle"));
+    }
+
+    #[test]
+    fn test_body_with_subtitle() {
+        let notif = Notification::new("Claude Code", "Task finished");
+        assert_eq!(notif.body_with_subtitle(), "Task finished");
+
+        let notif = notif.with_subtitle("myapp");
+        assert_eq!(notif.body_with_subtitle(), "myapp\nTask finished");
+    }
+
+    #[test]
+    fn test_notification_replace_id_serialization() {
+        let notif = Notification::new("Title", "Running tests...").with_replace_id("tests");
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(r#""replace_id":"tests""#));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.replace_id.as_deref(), Some("tests"));
+
+        let json = serde_json::to_string(&Notification::new("Title", "Body")).unwrap();
+        assert!(!json.contains("replace_id"));
+    }
+
+    #[test]
+    fn test_action_from_str() {
+        let action: NotificationAction = "approve:Approve:touch /tmp/ok".parse().unwrap();
+        assert_eq!(action.id, "approve");
+        assert_eq!(action.label, "Approve");
+        assert_eq!(action.command.as_deref(), Some("touch /tmp/ok"));
+
+        let action: NotificationAction = "deny:Deny".parse().unwrap();
+        assert_eq!(action.command, None);
+    }
+
+    #[test]
+    fn test_action_from_str_command_with_colons() {
+        let action: NotificationAction = "open:Open:open https://example.com:8080".parse().unwrap();
+        assert_eq!(
+            action.command.as_deref(),
+            Some("open https://example.com:8080")
+        );
+    }
+
+    #[test]
+    fn test_action_from_str_invalid() {
+        assert!("approve".parse::<NotificationAction>().is_err());
+        assert!(":Approve".parse::<NotificationAction>().is_err());
+        assert!("approve:".parse::<NotificationAction>().is_err());
+    }
+
+    #[test]
+    fn test_actions_serialization() {
+        let notif = Notification::new("Title", "Body")
+            .with_action("approve:Approve:echo yes".parse().unwrap())
+            .with_action("deny:Deny".parse().unwrap());
+
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains(
+            r#""actions":[{"id":"approve","label":"Approve","command":"echo yes"},{"id":"deny","label":"Deny"}]"#
+        ));
+
+        let parsed: Notification = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.actions, notif.actions);
+    }
+
+    #[test]
+    fn test_action_command_lookup() {
+        let notif = Notification::new("Title", "Body")
+            .with_action("approve:Approve:echo yes".parse().unwrap())
+            .with_action("deny:Deny".parse().unwrap());
+
+        assert_eq!(notif.action_command("approve"), Some("echo yes"));
+        assert_eq!(notif.action_command("deny"), None);
+        assert_eq!(notif.action_command("missing"), None);
     }
 
     #[test]
@@ -82,7 +566,7 @@ mod tests {
         assert_eq!(notif.title, "Title");
         assert_eq!(notif.body, "Body");
         assert_eq!(notif.icon, Some("test".to_string()));
-        assert_eq!(notif.activate, Some("bundle.id".to_string()));
+        assert_eq!(notif.activate, vec!["bundle.id".to_string()]);
     }
 
     #[test]
@@ -95,6 +579,11 @@ mod tests {
         assert!(json.contains("\"body\""));
         assert!(!json.contains("\"icon\""));
         assert!(!json.contains("\"activate\""));
+        assert!(!json.contains("\"urgency\""));
+        assert!(!json.contains("\"url\""));
+        assert!(!json.contains("\"sound\""));
+        assert!(!json.contains("\"actions\""));
+        assert!(!json.contains("\"sound_file\""));
         assert!(!json.contains("\"metadata\""));
     }
 
@@ -109,6 +598,20 @@ mod tests {
         assert!(json.contains("\"activate\""));
     }
 
+    #[test]
+    fn test_notification_open_url_alias() {
+        let json = r#"{"title":"PR ready","body":"Review it","open_url":"https://example.com/pr/1","activate":"com.apple.Terminal"}"#;
+        let notif: Notification = serde_json::from_str(json).unwrap();
+
+        assert_eq!(notif.url.as_deref(), Some("https://example.com/pr/1"));
+        assert_eq!(notif.activate, ["com.apple.Terminal"]);
+
+        // Written back out under its canonical name
+        let json = serde_json::to_string(&notif).unwrap();
+        assert!(json.contains("\"url\":\"https://example.com/pr/1\""));
+        assert!(!json.contains("open_url"));
+    }
+
     #[test]
     fn test_notification_deserialization() {
         let json = r#"{"title":"Test","body":"Message"}"#;
@@ -117,10 +620,29 @@ mod tests {
         assert_eq!(notif.title, "Test");
         assert_eq!(notif.body, "Message");
         assert!(notif.icon.is_none());
-        assert!(notif.activate.is_none());
+        assert!(notif.activate.is_empty());
         assert!(notif.metadata.is_empty());
     }
 
+    #[test]
+    fn test_notification_deserialization_activate_list() {
+        let json = r#"{"title":"Test","body":"Message","activate":["a.one","","a.two"]}"#;
+        let notif: Notification = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            notif.activate,
+            vec!["a.one".to_string(), "a.two".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_notification_deserialization_empty_activate() {
+        let json = r#"{"title":"Test","body":"Message","activate":""}"#;
+        let notif: Notification = serde_json::from_str(json).unwrap();
+
+        assert!(notif.activate.is_empty());
+    }
+
     #[test]
     fn test_notification_deserialization_with_all_fields() {
         let json = r#"{
@@ -135,8 +657,46 @@ mod tests {
         assert_eq!(notif.title, "Test");
         assert_eq!(notif.body, "Message");
         assert_eq!(notif.icon, Some("test-icon".to_string()));
-        assert_eq!(notif.activate, Some("test.bundle".to_string()));
+        assert_eq!(notif.activate, vec!["test.bundle".to_string()]);
         assert_eq!(notif.metadata.len(), 1);
         assert!(notif.metadata.contains_key("key"));
     }
+
+    #[test]
+    fn test_check_metadata_rejects_too_many_keys() {
+        let mut notif = Notification::new("Test", "Message");
+        notif.metadata = (0..=DEFAULT_MAX_METADATA_KEYS)
+            .map(|i| (format!("key{}", i), serde_json::Value::from(i)))
+            .collect();
+
+        let err = notif
+            .check_metadata(DEFAULT_MAX_METADATA_KEYS, DEFAULT_MAX_METADATA_BYTES)
+            .unwrap_err();
+        assert!(err.to_string().contains("keys"));
+        assert!(
+            notif
+                .check_metadata(DEFAULT_MAX_METADATA_KEYS + 1, DEFAULT_MAX_METADATA_BYTES)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_metadata_rejects_oversized_metadata() {
+        let json = serde_json::json!({
+            "title": "Test",
+            "body": "Message",
+            "metadata": {"blob": "x".repeat(DEFAULT_MAX_METADATA_BYTES)}
+        });
+        let notif: Notification = serde_json::from_value(json).unwrap();
+
+        let err = notif
+            .check_metadata(DEFAULT_MAX_METADATA_KEYS, DEFAULT_MAX_METADATA_BYTES)
+            .unwrap_err();
+        assert!(err.to_string().contains("bytes"));
+        assert!(
+            notif
+                .check_metadata(DEFAULT_MAX_METADATA_KEYS, 2 * DEFAULT_MAX_METADATA_BYTES)
+                .is_ok()
+        );
+    }
 }
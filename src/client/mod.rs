@@ -1,2 +1,11 @@
+pub mod agent;
+pub mod cooldown;
+pub mod history;
 pub mod message;
+pub mod quiet_hours;
+pub mod reverse_lines;
+pub mod rules;
 pub mod send;
+pub mod text;
+pub mod throttle;
+pub mod watch;
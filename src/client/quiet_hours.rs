@@ -0,0 +1,192 @@
+use chrono::{Datelike, Local, Timelike};
+use serde::{Deserialize, Deserializer};
+
+/// A daily window during which notifications are dropped, e.g. 22:00-08:00
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+    /// Local time the window starts (inclusive)
+    pub start: TimeOfDay,
+    /// Local time the window ends (exclusive); earlier than `start` wraps past midnight
+    pub end: TimeOfDay,
+    /// Days the window starts on; empty means every day
+    #[serde(default)]
+    pub days: Vec<Day>,
+    /// Let critical notifications through during quiet hours
+    #[serde(default)]
+    pub allow_critical: bool,
+}
+
+/// Minutes since local midnight, written as "HH:MM" in config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay(u16);
+
+impl TimeOfDay {
+    pub fn new(hour: u16, minute: u16) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Self(hour * 60 + minute))
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.split_once(':')
+            .and_then(|(h, m)| TimeOfDay::new(h.parse().ok()?, m.parse().ok()?))
+            .ok_or_else(|| {
+                serde::de::Error::custom(format!("invalid time '{}', expected HH:MM", s))
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Day {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Day {
+    const ALL: [Day; 7] = [
+        Day::Mon,
+        Day::Tue,
+        Day::Wed,
+        Day::Thu,
+        Day::Fri,
+        Day::Sat,
+        Day::Sun,
+    ];
+
+    /// Day from its index in the week, Monday = 0
+    fn from_index(index: u32) -> Day {
+        Day::ALL[index as usize % 7]
+    }
+
+    fn previous(self) -> Day {
+        Day::from_index(self as u32 + 6)
+    }
+}
+
+impl QuietHours {
+    /// Whether `time` on `day` falls inside the window.
+    ///
+    /// A window that wraps past midnight belongs to the day it starts on, so
+    /// with `days = ["fri"]`, 22:00-08:00 covers Friday night and early Saturday.
+    pub fn contains(&self, day: Day, time: TimeOfDay) -> bool {
+        let runs_on = |d: Day| self.days.is_empty() || self.days.contains(&d);
+
+        if self.start <= self.end {
+            runs_on(day) && self.start <= time && time < self.end
+        } else {
+            (runs_on(day) && time >= self.start) || (runs_on(day.previous()) && time < self.end)
+        }
+    }
+
+    /// Whether the current local time falls inside the window
+    pub fn active_now(&self) -> bool {
+        let now = Local::now();
+        let day = Day::from_index(now.weekday().num_days_from_monday());
+        // Hours and minutes from chrono are always in range
+        let time = TimeOfDay((now.hour() * 60 + now.minute()) as u16);
+        self.contains(day, time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u16, minute: u16) -> TimeOfDay {
+        TimeOfDay::new(hour, minute).unwrap()
+    }
+
+    fn window(start: TimeOfDay, end: TimeOfDay, days: Vec<Day>) -> QuietHours {
+        QuietHours {
+            start,
+            end,
+            days,
+            allow_critical: false,
+        }
+    }
+
+    #[test]
+    fn test_same_day_window() {
+        let quiet = window(at(12, 0), at(13, 30), vec![]);
+
+        assert!(!quiet.contains(Day::Mon, at(11, 59)));
+        assert!(quiet.contains(Day::Mon, at(12, 0)));
+        assert!(quiet.contains(Day::Mon, at(13, 29)));
+        assert!(!quiet.contains(Day::Mon, at(13, 30)));
+    }
+
+    #[test]
+    fn test_window_wrapping_midnight() {
+        let quiet = window(at(22, 0), at(8, 0), vec![]);
+
+        assert!(quiet.contains(Day::Tue, at(22, 0)));
+        assert!(quiet.contains(Day::Tue, at(23, 59)));
+        assert!(quiet.contains(Day::Wed, at(0, 0)));
+        assert!(quiet.contains(Day::Wed, at(7, 59)));
+        assert!(!quiet.contains(Day::Wed, at(8, 0)));
+        assert!(!quiet.contains(Day::Wed, at(21, 59)));
+    }
+
+    #[test]
+    fn test_wrapping_window_belongs_to_start_day() {
+        let quiet = window(at(22, 0), at(8, 0), vec![Day::Fri]);
+
+        assert!(quiet.contains(Day::Fri, at(23, 0)));
+        assert!(quiet.contains(Day::Sat, at(7, 0)));
+        assert!(!quiet.contains(Day::Fri, at(7, 0)));
+        assert!(!quiet.contains(Day::Sat, at(23, 0)));
+    }
+
+    #[test]
+    fn test_wrapping_window_sunday_into_monday() {
+        let quiet = window(at(23, 0), at(6, 0), vec![Day::Sun]);
+
+        assert!(quiet.contains(Day::Mon, at(5, 0)));
+        assert!(!quiet.contains(Day::Tue, at(5, 0)));
+    }
+
+    #[test]
+    fn test_equal_start_and_end_is_empty() {
+        let quiet = window(at(9, 0), at(9, 0), vec![]);
+
+        assert!(!quiet.contains(Day::Mon, at(9, 0)));
+        assert!(!quiet.contains(Day::Mon, at(3, 0)));
+    }
+
+    #[test]
+    fn test_time_of_day_bounds() {
+        assert_eq!(TimeOfDay::new(23, 59), Some(TimeOfDay(1439)));
+        assert_eq!(TimeOfDay::new(24, 0), None);
+        assert_eq!(TimeOfDay::new(12, 60), None);
+    }
+
+    #[test]
+    fn test_deserialize_quiet_hours() {
+        let quiet: QuietHours = toml::from_str(
+            "start = \"22:00\"\nend = \"08:30\"\ndays = [\"mon\", \"fri\"]\nallow_critical = true\n",
+        )
+        .unwrap();
+
+        assert_eq!(quiet.start, at(22, 0));
+        assert_eq!(quiet.end, at(8, 30));
+        assert_eq!(quiet.days, vec![Day::Mon, Day::Fri]);
+        assert!(quiet.allow_critical);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_time() {
+        assert!(toml::from_str::<QuietHours>("start = \"25:00\"\nend = \"08:00\"\n").is_err());
+        assert!(toml::from_str::<QuietHours>("start = \"late\"\nend = \"08:00\"\n").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// How much of the file to read per step when walking backwards
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Lines of a file from last to first, read backwards in chunks so that
+/// finding something near the end doesn't read the whole file.
+///
+/// Like `BufRead::lines`, a trailing `\r` is stripped. Blank lines, including
+/// the one after a final newline, are yielded as empty strings.
+pub struct ReverseLines<R> {
+    reader: R,
+    /// Offset where the not-yet-read part of the file ends
+    pos: u64,
+    /// The most recently read chunk, up to where lines have been returned
+    buf: Vec<u8>,
+    /// Earlier chunks with no newline, all part of the line being assembled,
+    /// from the end of the file backwards. Kept apart so a long line is
+    /// concatenated once instead of being copied on every chunk.
+    pending: Vec<Vec<u8>>,
+    done: bool,
+}
+
+impl<R: Read + Seek> ReverseLines<R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let pos = reader.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            reader,
+            pos,
+            buf: Vec::new(),
+            pending: Vec::new(),
+            done: false,
+        })
+    }
+
+    /// Set the buffer aside as part of the current line and read the chunk before `pos`
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let len = self.pos.min(CHUNK_SIZE as u64);
+        self.pos -= len;
+        self.reader.seek(SeekFrom::Start(self.pos))?;
+
+        let mut chunk = vec![0; len as usize];
+        self.reader.read_exact(&mut chunk)?;
+        self.pending.push(std::mem::replace(&mut self.buf, chunk));
+        Ok(())
+    }
+
+    /// `head` followed by the chunks set aside for the current line
+    fn assemble(&mut self, mut head: Vec<u8>) -> Vec<u8> {
+        for chunk in self.pending.drain(..).rev() {
+            head.extend_from_slice(&chunk);
+        }
+        head
+    }
+}
+
+fn into_line(mut bytes: Vec<u8>) -> io::Result<String> {
+    if bytes.last() == Some(&b'\r') {
+        bytes.pop();
+    }
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+impl<R: Read + Seek> Iterator for ReverseLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            if let Some(newline) = self.buf.iter().rposition(|&b| b == b'\n') {
+                let line = self.buf.split_off(newline + 1);
+                self.buf.truncate(newline);
+                return Some(into_line(self.assemble(line)));
+            }
+
+            // The start of the file ends the first line
+            if self.pos == 0 {
+                self.done = true;
+                let line = std::mem::take(&mut self.buf);
+                return Some(into_line(self.assemble(line)));
+            }
+
+            if let Err(e) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn reversed(content: &str) -> Vec<String> {
+        ReverseLines::new(Cursor::new(content.as_bytes()))
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lines_in_reverse() {
+        assert_eq!(reversed("one\ntwo\nthree"), ["three", "two", "one"]);
+    }
+
+    #[test]
+    fn test_trailing_newline_and_crlf() {
+        assert_eq!(reversed("one\r\ntwo\r\n"), ["", "two", "one"]);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(reversed(""), [""]);
+    }
+
+    #[test]
+    fn test_lines_spanning_chunks() {
+        let long = "x".repeat(CHUNK_SIZE * 2 + 17);
+        let multibyte = "é".repeat(CHUNK_SIZE);
+        let content = format!("first\n{}\n{}\nlast", long, multibyte);
+
+        assert_eq!(reversed(&content), ["last", &multibyte, &long, "first"]);
+    }
+
+    #[test]
+    fn test_multi_megabyte_line() {
+        let huge = "y".repeat(8 * 1024 * 1024 + 5);
+        let content = format!("first\n{}\nlast\n", huge);
+
+        let lines = reversed(&content);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "");
+        assert_eq!(lines[1], "last");
+        assert!(lines[2] == huge, "huge line was not reassembled intact");
+        assert_eq!(lines[3], "first");
+    }
+
+    #[test]
+    fn test_invalid_utf8_errors() {
+        let mut lines = ReverseLines::new(Cursor::new(b"ok\n\xff\xfe".to_vec())).unwrap();
+
+        assert!(lines.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::client::message::{Notification, Urgency};
+use crate::notify::remote::Forward;
+
+/// A `[[rules]]` entry from config.toml: when every `match` key equals the
+/// notification's metadata value, the rule's changes apply
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// Metadata key to expected value; an empty table matches every notification
+    #[serde(default, rename = "match")]
+    pub matches: HashMap<String, String>,
+    /// Drop the notification instead of delivering it
+    #[serde(default)]
+    pub suppress: bool,
+    /// Sound to play instead of the notification's own
+    pub sound: Option<String>,
+    /// Urgency to deliver with instead of the notification's own
+    pub urgency: Option<Urgency>,
+    /// Also POST the notification here, on top of any `[forward]` section
+    pub forward: Option<Forward>,
+}
+
+impl Rule {
+    /// Whether the notification's metadata has every value this rule matches on.
+    /// Non-string metadata is compared against the value parsed as JSON, so
+    /// `"3"` matches `3`.
+    pub fn matches(&self, notification: &Notification) -> bool {
+        self.matches.iter().all(|(key, expected)| {
+            notification
+                .metadata
+                .get(key)
+                .is_some_and(|value| match value {
+                    serde_json::Value::String(s) => s == expected,
+                    other => serde_json::from_str::<serde_json::Value>(expected)
+                        .is_ok_and(|expected| expected == *other),
+                })
+        })
+    }
+
+    /// Apply the rule's sound and urgency to the notification
+    pub fn apply(&self, notification: &mut Notification) {
+        if let Some(sound) = &self.sound {
+            notification.sound = Some(sound.clone());
+            notification.sound_file = None;
+        }
+        if let Some(urgency) = self.urgency {
+            notification.urgency = Some(urgency);
+        }
+    }
+}
+
+/// The first rule that matches; later rules are not consulted
+pub fn first_match<'a>(rules: &'a [Rule], notification: &Notification) -> Option<&'a Rule> {
+    rules.iter().find(|rule| rule.matches(notification))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(matches: &[(&str, &str)]) -> Rule {
+        Rule {
+            matches: matches
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            suppress: false,
+            sound: None,
+            urgency: None,
+            forward: None,
+        }
+    }
+
+    fn notification(metadata: serde_json::Value) -> Notification {
+        let mut notification = Notification::new("Claude Code", "Task finished");
+        notification.metadata = serde_json::from_value(metadata).unwrap();
+        notification
+    }
+
+    #[test]
+    fn test_rule_matches_all_keys() {
+        let rule = rule(&[("agent", "claude"), ("severity", "error")]);
+
+        assert!(rule.matches(&notification(
+            serde_json::json!({"agent": "claude", "severity": "error", "extra": 1})
+        )));
+        assert!(!rule.matches(&notification(serde_json::json!({"agent": "claude"}))));
+        assert!(!rule.matches(&notification(
+            serde_json::json!({"agent": "codex", "severity": "error"})
+        )));
+    }
+
+    #[test]
+    fn test_rule_matches_non_string_values() {
+        let rule = rule(&[("attempt", "3"), ("retry", "true")]);
+
+        assert!(rule.matches(&notification(
+            serde_json::json!({"attempt": 3, "retry": true})
+        )));
+        assert!(!rule.matches(&notification(
+            serde_json::json!({"attempt": 4, "retry": true})
+        )));
+    }
+
+    #[test]
+    fn test_empty_match_matches_everything() {
+        assert!(rule(&[]).matches(&Notification::new("Title", "Body")));
+    }
+
+    #[test]
+    fn test_first_match_precedence() {
+        let mut errors = rule(&[("severity", "error")]);
+        errors.urgency = Some(Urgency::Critical);
+        let mut claude = rule(&[("agent", "claude")]);
+        claude.suppress = true;
+        let rules = [errors, claude];
+
+        let error = notification(serde_json::json!({"agent": "claude", "severity": "error"}));
+        assert_eq!(first_match(&rules, &error), Some(&rules[0]));
+
+        let info = notification(serde_json::json!({"agent": "claude", "severity": "info"}));
+        assert_eq!(first_match(&rules, &info), Some(&rules[1]));
+
+        let other = notification(serde_json::json!({"agent": "codex"}));
+        assert_eq!(first_match(&rules, &other), None);
+    }
+
+    #[test]
+    fn test_apply_overrides_sound_and_urgency() {
+        let mut rule = rule(&[]);
+        rule.sound = Some("Submarine".to_string());
+        rule.urgency = Some(Urgency::Low);
+        let mut notification = Notification::new("Title", "Body")
+            .with_urgency(Urgency::Critical)
+            .with_sound_file("/tmp/alert.wav");
+
+        rule.apply(&mut notification);
+
+        assert_eq!(notification.sound.as_deref(), Some("Submarine"));
+        assert!(notification.sound_file.is_none());
+        assert_eq!(notification.urgency, Some(Urgency::Low));
+    }
+
+    #[test]
+    fn test_rule_from_toml() {
+        let rule: Rule = toml::from_str(
+            "match = { agent = \"claude\" }\nsound = \"none\"\n[forward]\nurl = \"https://ntfy.sh/agents\"\nkind = \"ntfy\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rule.matches.get("agent").map(String::as_str),
+            Some("claude")
+        );
+        assert_eq!(rule.sound.as_deref(), Some("none"));
+        assert_eq!(rule.forward.unwrap().url, "https://ntfy.sh/agents");
+    }
+}
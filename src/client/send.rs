@@ -1,11 +1,35 @@
 use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Read};
-use tracing::info;
-
-use crate::client::message::Notification;
-use crate::notify;
+use std::io::{self, Read, Seek};
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::client::agent::Agent;
+use crate::client::cooldown;
+use crate::client::history;
+use crate::client::message::{self, Notification, NotificationAction, Urgency};
+use crate::client::reverse_lines::ReverseLines;
+use crate::client::rules;
+use crate::client::text;
+use crate::client::throttle::{self, History, Limits, Verdict};
+use crate::client::watch;
+use crate::config::{self, Config};
+use crate::i18n::{self, Phrase};
+use crate::notify::NotificationBackend;
+use crate::notify::remote::{self, Backend, Forward};
+
+const DEFAULT_TITLE: &str = "Ahoy";
+
+/// How often to check a watched transcript for new activity
+const WATCH_POLL: Duration = Duration::from_secs(1);
+
+/// Env var naming a transcript to use when hook data has no `transcript_path`
+const TRANSCRIPT_ENV: &str = "AHOY_TRANSCRIPT";
 
 /// Claude Code hook stdin data
 #[derive(Deserialize)]
@@ -20,12 +44,49 @@ struct ClaudeHookData {
     hook_event_name: Option<String>,
 }
 
+/// Codex `notify` payload, passed as the notify program's last argument
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct CodexNotifyData {
+    #[serde(rename = "type")]
+    event_type: Option<String>,
+    cwd: Option<String>,
+    last_assistant_message: Option<String>,
+    #[serde(default)]
+    input_messages: Vec<String>,
+}
+
+/// Gemini CLI hook stdin data
+#[derive(Deserialize)]
+struct GeminiHookData {
+    cwd: Option<String>,
+    hook_event_name: Option<String>,
+    /// The user's prompt (AfterAgent)
+    prompt: Option<String>,
+    /// Gemini's final reply (AfterAgent)
+    prompt_response: Option<String>,
+    /// Notification text, e.g. for a tool permission request (Notification)
+    message: Option<String>,
+    tool_name: Option<String>,
+    tool_input: Option<serde_json::Value>,
+}
+
 /// A line from the Claude transcript
 #[derive(Deserialize)]
 struct TranscriptLine {
     #[serde(rename = "type")]
     line_type: Option<String>,
     message: Option<TranscriptMessage>,
+    /// Streaming event for `stream_event` lines (stream-json format)
+    event: Option<serde_json::Value>,
+    /// When the line was written, as RFC 3339 with milliseconds
+    timestamp: Option<String>,
+}
+
+/// A message being reassembled from stream-json delta events
+struct StreamedMessage {
+    role: String,
+    text: String,
 }
 
 #[derive(Deserialize)]
@@ -33,49 +94,587 @@ struct TranscriptMessage {
     content: Option<serde_json::Value>,
 }
 
-pub fn run(
-    message: Option<String>,
-    title: String,
-    json: Option<String>,
-    from_claude: bool,
-    activate: Option<String>,
-) -> Result<()> {
-    let mut notification = if from_claude {
-        build_from_claude_stdin(&title)?
-    } else if let Some(json_str) = json {
-        serde_json::from_str(&json_str)?
-    } else if let Some(body) = message {
+/// Options for `ahoy send`, mirroring the CLI flags
+#[derive(Debug, Default, Clone)]
+pub struct SendOptions {
+    pub message: Option<String>,
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub json: Option<String>,
+    pub json_stdin: bool,
+    pub hook_format: Option<HookFormat>,
+    pub from_codex: bool,
+    pub activate: Vec<String>,
+    pub actions: Vec<NotificationAction>,
+    pub link: Option<String>,
+    pub icon: Option<String>,
+    pub sound: Option<String>,
+    pub sound_file: Option<String>,
+    pub timeout_ms: Option<u32>,
+    pub replace_id: Option<String>,
+    pub agent: Option<Agent>,
+    pub urgency: Option<Urgency>,
+    pub urgency_from_exit: Option<i32>,
+    pub strip_markdown: bool,
+    pub include_tool_result: bool,
+    pub summary_only: bool,
+    pub summary: SummarySource,
+    pub body_lines: usize,
+    pub strict: bool,
+    pub template: Option<String>,
+    pub watch_transcript: Option<PathBuf>,
+    pub notify_on_idle_secs: Option<u64>,
+    pub cooldown_ms: Option<u64>,
+    pub force: bool,
+    /// Fail when any remote backend fails, not only the local notification
+    pub exit_on_failure: bool,
+    /// Print the notification as JSON instead of delivering it
+    pub dry_run: bool,
+}
+
+/// Agent whose hook payload `ahoy send` reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookFormat {
+    /// Claude Code hook JSON
+    Claude,
+    /// The JSON Codex passes to its `notify` program
+    Codex,
+    /// Gemini CLI hook JSON
+    Gemini,
+}
+
+impl HookFormat {
+    pub fn parser(self) -> &'static dyn HookParser {
+        match self {
+            HookFormat::Claude => &ClaudeHook,
+            HookFormat::Codex => &CodexHook,
+            HookFormat::Gemini => &GeminiHook,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            HookFormat::Claude => "Claude",
+            HookFormat::Codex => "Codex",
+            HookFormat::Gemini => "Gemini",
+        }
+    }
+}
+
+/// Turns an agent's hook payload into a notification
+pub trait HookParser {
+    fn parse(&self, payload: &str, title: &str, options: &HookOptions) -> Result<Notification>;
+}
+
+struct ClaudeHook;
+struct CodexHook;
+struct GeminiHook;
+
+impl HookParser for ClaudeHook {
+    fn parse(&self, payload: &str, title: &str, options: &HookOptions) -> Result<Notification> {
+        build_from_claude_payload(payload, title, options)
+    }
+}
+
+impl HookParser for CodexHook {
+    fn parse(&self, payload: &str, title: &str, options: &HookOptions) -> Result<Notification> {
+        build_from_codex_payload(payload, title, options)
+    }
+}
+
+impl HookParser for GeminiHook {
+    fn parse(&self, payload: &str, title: &str, options: &HookOptions) -> Result<Notification> {
+        build_from_gemini_payload(payload, title, options)
+    }
+}
+
+/// Whose message from the transcript a Claude notification shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SummarySource {
+    /// The last prompt sent to the agent
+    #[default]
+    User,
+    /// The agent's last reply
+    Assistant,
+}
+
+impl SummarySource {
+    /// Transcript role of the messages to look for
+    fn role(self) -> &'static str {
+        match self {
+            SummarySource::User => "user",
+            SummarySource::Assistant => "assistant",
+        }
+    }
+}
+
+/// Longest message body taken from hook data, unless `max_body_chars` is configured
+pub const DEFAULT_MAX_BODY_CHARS: usize = 100;
+
+/// Longest tool command or path shown, unless `max_tool_chars` is configured
+pub const DEFAULT_MAX_TOOL_CHARS: usize = 60;
+
+/// Options controlling how Claude hook data becomes a notification
+#[derive(Debug)]
+pub struct HookOptions {
+    /// Summarize the last tool result instead of echoing the last prompt
+    pub include_tool_result: bool,
+    /// Shorten long text to its first sentence rather than cutting it mid-word
+    pub summary_only: bool,
+    /// Whose last message to show when summarizing from the transcript
+    pub summary: SummarySource,
+    /// How many lines of the selected message to include (0 is treated as 1)
+    pub body_lines: usize,
+    /// Fail on empty or incomplete hook data instead of a generic message
+    pub strict: bool,
+    /// Body template with `{project}`, `{cwd}`, `{tool}`, `{prompt}` and
+    /// `{message}` placeholders; unset uses [`DEFAULT_HOOK_TEMPLATE`]
+    pub template: Option<String>,
+    /// Truncate the prompt or reply to this many characters
+    pub max_body_chars: usize,
+    /// Truncate a tool's command or file path to this many characters
+    pub max_tool_chars: usize,
+}
+
+impl Default for HookOptions {
+    fn default() -> Self {
+        Self {
+            include_tool_result: false,
+            summary_only: false,
+            summary: SummarySource::default(),
+            body_lines: 0,
+            strict: false,
+            template: None,
+            max_body_chars: DEFAULT_MAX_BODY_CHARS,
+            max_tool_chars: DEFAULT_MAX_TOOL_CHARS,
+        }
+    }
+}
+
+/// Body of a notification built from hook data when no template is configured
+pub const DEFAULT_HOOK_TEMPLATE: &str = "[{project}] {message}";
+
+/// Placeholder values for a hook notification. `message` is what the default
+/// template shows: the tool description for tool events, otherwise the prompt.
+fn hook_vars(
+    cwd: Option<&str>,
+    project: &str,
+    tool: Option<&str>,
+    prompt: &str,
+    message: String,
+) -> HashMap<&'static str, String> {
+    HashMap::from([
+        ("cwd", cwd.unwrap_or_default().to_string()),
+        ("project", project.to_string()),
+        ("tool", tool.unwrap_or_default().to_string()),
+        ("prompt", prompt.to_string()),
+        ("message", message),
+    ])
+}
+
+/// Fill the title and body templates from hook data
+fn hook_notification(
+    title: &str,
+    default_template: &str,
+    options: &HookOptions,
+    vars: &HashMap<&str, String>,
+) -> Notification {
+    let template = options.template.as_deref().unwrap_or(default_template);
+    Notification::new(
+        text::render_template(title, vars),
+        text::render_template(template, vars),
+    )
+}
+
+pub fn run(mut opts: SendOptions, config: &Config) -> Result<()> {
+    // Watchdog mode: wait for the transcript to go quiet before notifying
+    if let (Some(path), Some(secs)) = (&opts.watch_transcript, opts.notify_on_idle_secs) {
+        info!("Watching {} for {}s of inactivity", path.display(), secs);
+        watch::wait_until_idle(path, Duration::from_secs(secs), WATCH_POLL)?;
+        if opts.message.is_none()
+            && opts.json.is_none()
+            && !opts.json_stdin
+            && opts.hook_format.is_none()
+        {
+            opts.message = Some(i18n::text(Phrase::AgentIdle).to_string());
+        }
+    }
+
+    if opts.json_stdin {
+        opts.json = Some(read_json_stdin(io::stdin())?);
+        opts.json_stdin = false;
+    }
+    if let Some(items) = opts.json.as_deref().and_then(batch_items) {
+        return run_batch(opts, items, config);
+    }
+
+    run_one(opts, config)
+}
+
+/// Send each notification of a JSON array in order. A failing entry is logged
+/// and skipped, so it can't hold back the ones after it.
+fn run_batch(opts: SendOptions, items: Vec<String>, config: &Config) -> Result<()> {
+    let total = items.len();
+    let mut failed = 0;
+    for (i, item) in items.into_iter().enumerate() {
+        let opts = SendOptions {
+            json: Some(item),
+            ..opts.clone()
+        };
+        if let Err(e) = run_one(opts, config) {
+            warn!("Notification {} of {} failed: {:#}", i + 1, total, e);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} notifications failed", failed, total);
+    }
+    Ok(())
+}
+
+/// Filter and deliver a single notification
+fn run_one(opts: SendOptions, config: &Config) -> Result<()> {
+    let cooldown_ms = opts.cooldown_ms;
+    let force = opts.force;
+    let dry_run = opts.dry_run;
+    let exit_on_failure = opts.exit_on_failure;
+    let mut notification = build_notification(opts, config)?;
+
+    let rule = rules::first_match(&config.rules, &notification);
+    if let Some(rule) = rule {
+        rule.apply(&mut notification);
+    }
+
+    if dry_run {
+        println!("{}", serde_json::to_string_pretty(&notification)?);
+        return Ok(());
+    }
+
+    if let Some(rule) = rule
+        && rule.suppress
+    {
+        info!(
+            "Dropping notification matched by a rule: {:?}",
+            notification
+        );
+        return Ok(());
+    }
+
+    let critical = notification.urgency == Some(Urgency::Critical);
+
+    if !force
+        && let Some(quiet_hours) = &config.quiet_hours
+        && !(critical && quiet_hours.allow_critical)
+        && quiet_hours.active_now()
+    {
+        info!(
+            "Dropping notification during quiet hours: {:?}",
+            notification
+        );
+        return Ok(());
+    }
+
+    let state_path = config::last_notification_path();
+    let now = cooldown::now_ms();
+
+    // Critical notifications always get through
+    if let Some(cooldown_ms) = cooldown_ms
+        && !critical
+        && cooldown::within_cooldown(cooldown::last_shown(&state_path), now, cooldown_ms)
+    {
+        info!(
+            "Dropping notification within {}ms cooldown: {:?}",
+            cooldown_ms, notification
+        );
+        return Ok(());
+    }
+
+    let history_path = config::recent_notifications_path();
+    let mut history = History::load(&history_path);
+    let key = throttle::content_key(&notification.title, &notification.body);
+
+    let verdict = if critical {
+        Verdict::Show
+    } else {
+        history.check(key, now, &Limits::from_config(config))
+    };
+    if verdict != Verdict::Show {
+        let total = history.record_suppressed();
+        debug!(
+            "Suppressed notification ({:?}, {} suppressed so far): {:?}",
+            verdict, total, notification
+        );
+        if let Err(e) = history.save(&history_path) {
+            warn!("Could not record suppressed notification: {}", e);
+        }
+        return Ok(());
+    }
+
+    send_notification(
+        &notification,
+        config,
+        config.local_backend.backend().as_ref(),
+        rule.and_then(|r| r.forward.as_ref()),
+        exit_on_failure,
+    )?;
+
+    if let Err(e) = cooldown::record_shown(&state_path, now) {
+        warn!("Could not record notification time: {}", e);
+    }
+    history.record_shown(key, now);
+    if let Err(e) = history.save(&history_path) {
+        warn!("Could not record notification history: {}", e);
+    }
+    if let Err(e) = history::record(&notification, config) {
+        warn!("Could not append to notification log: {:#}", e);
+    }
+    Ok(())
+}
+
+/// Resolve the notification to deliver from the send options
+fn build_notification(opts: SendOptions, config: &Config) -> Result<Notification> {
+    let default_title = config.default_title.as_deref().unwrap_or(DEFAULT_TITLE);
+
+    // An explicit --title wins over the agent's or exit code's default title
+    let title = opts
+        .title
+        .or_else(|| opts.agent.map(|a| a.title().to_string()))
+        .or_else(|| opts.urgency_from_exit.map(exit_title))
+        .unwrap_or_else(|| default_title.to_string());
+
+    let hook_options = HookOptions {
+        include_tool_result: opts.include_tool_result,
+        summary_only: opts.summary_only,
+        summary: opts.summary,
+        body_lines: opts.body_lines,
+        strict: opts.strict,
+        template: opts.template.or_else(|| config.hook_template.clone()),
+        max_body_chars: config.max_body_chars.unwrap_or(DEFAULT_MAX_BODY_CHARS),
+        max_tool_chars: config.max_tool_chars.unwrap_or(DEFAULT_MAX_TOOL_CHARS),
+    };
+    let mut notification = if let Some(format) = opts.hook_format {
+        build_from_hook_reader(io::stdin(), format, &title, &hook_options)?
+    } else if opts.from_codex {
+        let payload = opts
+            .message
+            .context("--from-codex expects the Codex notify payload as the message")?;
+        HookFormat::Codex
+            .parser()
+            .parse(&payload, &title, &hook_options)?
+    } else if let Some(json_str) = opts.json {
+        let notification: Notification =
+            serde_json::from_str(&json_str).context("Failed to parse notification JSON")?;
+        notification
+            .check_metadata(
+                config
+                    .max_metadata_keys
+                    .unwrap_or(message::DEFAULT_MAX_METADATA_KEYS),
+                config
+                    .max_metadata_bytes
+                    .unwrap_or(message::DEFAULT_MAX_METADATA_BYTES),
+            )
+            .context("Rejected notification JSON")?;
+        notification
+    } else if let Some(body) = opts.message {
         Notification::new(title, body)
     } else {
-        bail!("Either a message or --json must be provided");
+        bail!("Either a message, --json or --json-stdin must be provided");
     };
 
-    // Apply activate if provided (overrides any value from JSON/stdin)
-    if let Some(bundle_id) = activate {
-        notification.activate = Some(bundle_id);
+    // Apply activate if provided (overrides any value from JSON/stdin).
+    // An empty bundle id (e.g. unset $__CFBundleIdentifier) counts as not provided.
+    let activate: Vec<String> = opts
+        .activate
+        .into_iter()
+        .filter(|b| !b.is_empty())
+        .collect();
+    if !activate.is_empty() {
+        notification.activate = activate;
+    }
+
+    // Actions from the CLI replace any from JSON
+    if !opts.actions.is_empty() {
+        notification.actions = opts.actions;
+    }
+
+    if let Some(url) = opts.link {
+        notification.url = Some(url);
+    }
+
+    // Stripping runs on the already-truncated body, so it can only shorten it
+    if opts.strip_markdown {
+        notification.body = text::strip_markdown(&notification.body);
+    }
+
+    if let Some(subtitle) = opts.subtitle {
+        notification.subtitle = Some(subtitle);
+    }
+
+    notification.title = text::sanitize(&notification.title);
+    notification.body = text::sanitize(&notification.body);
+    notification.subtitle = notification
+        .subtitle
+        .as_deref()
+        .map(text::sanitize)
+        .filter(|subtitle| !subtitle.is_empty());
+    fill_empty_fields(&mut notification, default_title);
+
+    // An explicit --urgency wins over one derived from an exit code
+    if let Some(urgency) = opts
+        .urgency
+        .or_else(|| opts.urgency_from_exit.map(Urgency::from_exit_code))
+    {
+        notification.urgency = Some(urgency);
+    }
+
+    if let Some(icon) = opts.icon {
+        notification.icon = Some(icon);
+    }
+    if notification.icon.is_none() {
+        notification.icon = config.default_icon.clone();
+    }
+
+    if let Some(sound) = opts.sound {
+        notification.sound = Some(sound);
+    }
+
+    if let Some(path) = opts.sound_file {
+        notification.sound_file = Some(path);
+    }
+
+    if let Some(timeout_ms) = opts.timeout_ms {
+        notification.timeout_ms = Some(timeout_ms);
+    }
+
+    if let Some(key) = opts.replace_id {
+        notification.replace_id = Some(key);
+    }
+
+    // Lets config rules match on the agent
+    if let Some(agent) = opts.agent {
+        notification
+            .metadata
+            .entry("agent".to_string())
+            .or_insert_with(|| agent.name().into());
+    }
+
+    Ok(notification)
+}
+
+/// Replace a blank title or body, which would otherwise show an empty banner
+fn fill_empty_fields(notification: &mut Notification, default_title: &str) {
+    if notification.title.trim().is_empty() {
+        notification.title = default_title.to_string();
     }
+    if notification.body.trim().is_empty() {
+        notification.body = i18n::text(Phrase::NoMessage).to_string();
+    }
+}
+
+/// Default title for a wrapped command that exited with `code`
+fn exit_title(code: i32) -> String {
+    if code == 0 {
+        "Succeeded".to_string()
+    } else {
+        format!("Failed (exit {})", code)
+    }
+}
+
+/// Build a notification from the JSON Codex passes to its `notify` program
+fn build_from_codex_payload(
+    payload: &str,
+    title: &str,
+    options: &HookOptions,
+) -> Result<Notification> {
+    let data: CodexNotifyData =
+        serde_json::from_str(payload).context("Failed to parse Codex notify payload")?;
+
+    // Only turn completion is defined today; other events get the generic text
+    let text = if data.event_type.as_deref() == Some("agent-turn-complete") {
+        data.last_assistant_message
+            .as_deref()
+            .or(data.input_messages.last().map(String::as_str))
+            .and_then(|m| m.lines().map(str::trim).find(|l| !l.is_empty()))
+    } else {
+        None
+    }
+    .unwrap_or(i18n::text(Phrase::TaskFinished));
+
+    let text = text::truncate(text, options.max_body_chars);
+
+    let project = data
+        .cwd
+        .as_deref()
+        .and_then(|cwd| cwd.split('/').next_back());
+    let default_template = match project {
+        Some(_) => DEFAULT_HOOK_TEMPLATE,
+        None => "{message}",
+    };
+    let prompt = data.input_messages.last().map(String::as_str);
+    let vars = hook_vars(
+        data.cwd.as_deref(),
+        project.unwrap_or_default(),
+        None,
+        prompt.unwrap_or_default(),
+        text,
+    );
+
+    Ok(hook_notification(title, default_template, options, &vars))
+}
 
-    send_notification(&notification)
+/// Read the JSON document given with --json-stdin
+fn read_json_stdin(mut reader: impl Read) -> Result<String> {
+    let mut json = String::new();
+    reader
+        .read_to_string(&mut json)
+        .context("Failed to read notification JSON from stdin")?;
+    Ok(json)
 }
 
-fn build_from_claude_stdin(title: &str) -> Result<Notification> {
-    build_from_claude_stdin_reader(io::stdin(), title)
+/// The JSON of each notification in a batch, if `json` is an array rather
+/// than a single notification object
+fn batch_items(json: &str) -> Option<Vec<String>> {
+    match serde_json::from_str(json).ok()? {
+        serde_json::Value::Array(items) => Some(items.iter().map(ToString::to_string).collect()),
+        _ => None,
+    }
 }
 
 // Internal function for testing - accepts any reader
-fn build_from_claude_stdin_reader(mut reader: impl Read, title: &str) -> Result<Notification> {
+fn build_from_hook_reader(
+    mut reader: impl Read,
+    format: HookFormat,
+    title: &str,
+    options: &HookOptions,
+) -> Result<Notification> {
     let mut stdin_data = String::new();
     reader.read_to_string(&mut stdin_data)?;
 
     if stdin_data.is_empty() {
+        if options.strict {
+            anyhow::bail!("No {} hook data on stdin", format.name());
+        }
         return Ok(Notification::new(
             title.to_string(),
-            "Task finished".to_string(),
+            i18n::text(Phrase::TaskFinished),
         ));
     }
 
+    format.parser().parse(&stdin_data, title, options)
+}
+
+fn build_from_claude_payload(
+    payload: &str,
+    title: &str,
+    options: &HookOptions,
+) -> Result<Notification> {
     let hook_data: ClaudeHookData =
-        serde_json::from_str(&stdin_data).context("Failed to parse Claude hook data from stdin")?;
+        serde_json::from_str(payload).context("Failed to parse Claude hook data from stdin")?;
+
+    if options.strict && hook_data.cwd.is_none() {
+        anyhow::bail!("Claude hook data has no cwd");
+    }
 
     let project_name = hook_data
         .cwd
@@ -84,104 +683,602 @@ fn build_from_claude_stdin_reader(mut reader: impl Read, title: &str) -> Result<
         .unwrap_or("project");
 
     if let Some(tool_name) = &hook_data.tool_name {
-        let tool_desc = if let Some(input) = &hook_data.tool_input {
-            // Try to get command for Bash, or file_path for Read/Write/Edit
+        let message = tool_message(
+            tool_name,
+            hook_data.tool_input.as_ref(),
+            options.max_tool_chars,
+        );
+        let vars = hook_vars(
+            hook_data.cwd.as_deref(),
+            project_name,
+            Some(tool_name),
+            "",
+            message,
+        );
+        return Ok(hook_notification(
+            title,
+            DEFAULT_HOOK_TEMPLATE,
+            options,
+            &vars,
+        ));
+    }
+
+    // stdin's transcript_path takes precedence over the env fallback
+    let transcript_path = hook_data
+        .transcript_path
+        .or_else(|| std::env::var(TRANSCRIPT_ENV).ok());
+
+    let task = if let Some(transcript_path) = &transcript_path {
+        let role = options.summary.role();
+        let task = File::open(transcript_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                summarize_transcript(file, role, options.body_lines, options.include_tool_result)
+            })
+            .and_then(|task| {
+                if options.strict && task.tool_result.is_none() && task.message.is_none() {
+                    bail!("No {} message found in transcript", role);
+                }
+                Ok(task)
+            })
+            .with_context(|| format!("Failed to read last message from {}", transcript_path));
+        if options.strict {
+            task?
+        } else {
+            task.unwrap_or_default()
+        }
+    } else if options.strict {
+        anyhow::bail!(
+            "Claude hook data has neither tool_name nor transcript_path, and {} is unset",
+            TRANSCRIPT_ENV
+        );
+    } else {
+        TaskSummary::default()
+    };
+
+    // When the task's length is known the body says so, and the summary is
+    // left to {prompt}
+    let summary = task
+        .tool_result
+        .or(task.message)
+        .map(|summary| shorten(&summary, options));
+    let duration = task.duration_ms.map(format_duration);
+    let message = match (&duration, &summary) {
+        (Some(duration), _) => format!("{} {}", i18n::text(Phrase::DoneIn), duration),
+        (None, Some(summary)) => summary.clone(),
+        (None, None) => i18n::text(Phrase::TaskFinished).to_string(),
+    };
+    let prompt = summary.unwrap_or_else(|| message.clone());
+
+    let mut vars = hook_vars(
+        hook_data.cwd.as_deref(),
+        project_name,
+        None,
+        &prompt,
+        message,
+    );
+    vars.insert("duration", duration.unwrap_or_default());
+
+    Ok(hook_notification(
+        title,
+        DEFAULT_HOOK_TEMPLATE,
+        options,
+        &vars,
+    ))
+}
+
+/// Describe a tool call, naming the command or file it touches when known
+fn tool_message(
+    tool_name: &str,
+    tool_input: Option<&serde_json::Value>,
+    max_chars: usize,
+) -> String {
+    // Try to get command for Bash, or file_path for Read/Write/Edit
+    let tool_desc = tool_input
+        .and_then(|input| {
             input
                 .get("command")
                 .or_else(|| input.get("file_path"))
                 .or_else(|| input.get("pattern"))
-                .and_then(|v| v.as_str())
-                .map(|s| {
-                    if s.len() > 60 {
-                        format!("{}...", &s[..57])
-                    } else {
-                        s.to_string()
-                    }
-                })
-                .unwrap_or_default()
-        } else {
-            String::new()
-        };
+        })
+        .and_then(|v| v.as_str())
+        .map(|s| text::truncate(s, max_chars))
+        .unwrap_or_default();
 
-        let body = if tool_desc.is_empty() {
-            format!("[{}] Needs permission: {}", project_name, tool_name)
-        } else {
-            format!("[{}] {}: {}", project_name, tool_name, tool_desc)
-        };
+    if tool_desc.is_empty() {
+        format!("{}: {}", i18n::text(Phrase::NeedsPermission), tool_name)
+    } else {
+        format!("{}: {}", tool_name, tool_desc)
+    }
+}
 
-        return Ok(Notification::new(title.to_string(), body));
+/// Truncate a message longer than `max_body_chars`, or cut it at its first
+/// sentence with `summary_only`
+fn shorten(message: &str, options: &HookOptions) -> String {
+    let max_chars = options.max_body_chars;
+    let summary = (options.summary_only && message.chars().count() > max_chars)
+        .then(|| text::first_sentence(message, max_chars))
+        .flatten();
+    match summary {
+        Some(summary) => summary.to_string(),
+        None => text::truncate(message, max_chars),
     }
+}
 
-    let last_prompt = if let Some(transcript_path) = &hook_data.transcript_path {
-        extract_last_prompt(transcript_path).unwrap_or_else(|_| "Task finished".to_string())
-    } else {
-        "Task finished".to_string()
-    };
+/// Build a notification from Gemini CLI hook data
+fn build_from_gemini_payload(
+    payload: &str,
+    title: &str,
+    options: &HookOptions,
+) -> Result<Notification> {
+    let hook_data: GeminiHookData =
+        serde_json::from_str(payload).context("Failed to parse Gemini hook data from stdin")?;
+
+    if options.strict && hook_data.cwd.is_none() {
+        anyhow::bail!("Gemini hook data has no cwd");
+    }
 
-    // Truncate prompt if too long (max 100 chars for notification)
-    let truncated_prompt = if last_prompt.len() > 100 {
-        format!("{}...", &last_prompt[..97])
-    } else {
-        last_prompt
+    let project_name = hook_data
+        .cwd
+        .as_deref()
+        .and_then(|cwd| cwd.split('/').next_back())
+        .unwrap_or("project");
+
+    if let Some(tool_name) = &hook_data.tool_name {
+        let message = tool_message(
+            tool_name,
+            hook_data.tool_input.as_ref(),
+            options.max_tool_chars,
+        );
+        let vars = hook_vars(
+            hook_data.cwd.as_deref(),
+            project_name,
+            Some(tool_name),
+            "",
+            message,
+        );
+        return Ok(hook_notification(
+            title,
+            DEFAULT_HOOK_TEMPLATE,
+            options,
+            &vars,
+        ));
+    }
+
+    let text = match hook_data.hook_event_name.as_deref() {
+        Some("Notification") => hook_data.message,
+        _ => match options.summary {
+            SummarySource::User => hook_data.prompt,
+            SummarySource::Assistant => hook_data.prompt_response,
+        },
+    }
+    .map(|text| clean_message(&text, options.body_lines))
+    .filter(|text| !text.is_empty());
+
+    let text = match text {
+        Some(text) => text,
+        None if options.strict => anyhow::bail!(
+            "Gemini hook data for {} has no message text",
+            hook_data
+                .hook_event_name
+                .as_deref()
+                .unwrap_or("an unknown event")
+        ),
+        None => i18n::text(Phrase::TaskFinished).to_string(),
     };
 
-    let body = format!("[{}] {}", project_name, truncated_prompt);
+    let prompt = shorten(&text, options);
+    let vars = hook_vars(
+        hook_data.cwd.as_deref(),
+        project_name,
+        None,
+        &prompt,
+        prompt.clone(),
+    );
+    Ok(hook_notification(
+        title,
+        DEFAULT_HOOK_TEMPLATE,
+        options,
+        &vars,
+    ))
+}
 
-    Ok(Notification::new(title.to_string(), body))
+/// What one backward pass over a transcript found about the last task
+#[derive(Debug, Default)]
+struct TaskSummary {
+    /// Last message from the role the summary comes from
+    message: Option<String>,
+    /// First line of the last tool result, when asked for
+    tool_result: Option<String>,
+    /// Time from the user's last prompt to the final entry
+    duration_ms: Option<u64>,
 }
 
-fn extract_last_prompt(transcript_path: &str) -> Result<String> {
-    let file = File::open(transcript_path)?;
-    let reader = BufReader::new(file);
+/// Read the transcript backwards once, stopping as soon as the last message,
+/// the last tool result (with `include_tool_result`) and the task's duration
+/// are all known, so long sessions don't have to be read in full
+fn summarize_transcript(
+    transcript: impl Read + Seek,
+    role: &str,
+    body_lines: usize,
+    include_tool_result: bool,
+) -> Result<TaskSummary> {
+    let mut summary = TaskSummary::default();
+    let mut message = Some(LastMessage::new(role, body_lines));
+    let mut tool_result = include_tool_result;
+    let mut duration = Some(TaskDuration::default());
+
+    for line in ReverseLines::new(transcript)? {
+        if message.is_none() && !tool_result && duration.is_none() {
+            break;
+        }
+        let Ok(entry) = serde_json::from_str::<TranscriptLine>(&line?) else {
+            continue;
+        };
+
+        if let Some(finder) = &mut message
+            && let ControlFlow::Break(found) = finder.feed(&entry)
+        {
+            summary.message = found;
+            message = None;
+        }
+        if tool_result && let ControlFlow::Break(found) = last_tool_result(&entry) {
+            summary.tool_result = found;
+            tool_result = false;
+        }
+        if let Some(finder) = &mut duration
+            && let ControlFlow::Break(found) = finder.feed(&entry)
+        {
+            summary.duration_ms = found;
+            duration = None;
+        }
+    }
+
+    if let Some(finder) = message {
+        summary.message = finder.finish();
+    }
+    Ok(summary)
+}
 
-    let mut last_user_content: Option<String> = None;
+/// Finds the last message from a role with non-empty text, fed transcript
+/// lines latest first.
+///
+/// Streamed messages count at the position of their `message_stop` event,
+/// so they are reassembled once the scan reaches their `message_start`.
+struct LastMessage<'a> {
+    role: &'a str,
+    body_lines: usize,
+    /// Stream events since the last `message_start` seen, latest first
+    stream_events: Vec<serde_json::Value>,
+    /// A plain message that came after an unfinished streamed message's start
+    /// but before its stop, so it only wins if that message isn't from `role`
+    fallback: Option<String>,
+}
 
-    for line in reader.lines() {
-        let line = line?;
-        if line.is_empty() {
-            continue;
+impl<'a> LastMessage<'a> {
+    fn new(role: &'a str, body_lines: usize) -> Self {
+        LastMessage {
+            role,
+            body_lines,
+            stream_events: Vec::new(),
+            fallback: None,
         }
+    }
 
-        if let Ok(entry) = serde_json::from_str::<TranscriptLine>(&line)
-            && entry.line_type.as_deref() == Some("user")
-            && let Some(msg) = entry.message
-            && let Some(content) = msg.content
-        {
-            // Content can be a string or array
-            let text = match content {
-                serde_json::Value::String(s) => s,
-                serde_json::Value::Array(arr) => arr
+    /// Look at the next line back, breaking once the message is settled
+    fn feed(&mut self, entry: &TranscriptLine) -> ControlFlow<Option<String>> {
+        match entry.line_type.as_deref() {
+            Some(line_type) if line_type == self.role => {
+                let Some(text) = entry
+                    .message
+                    .as_ref()
+                    .and_then(|msg| msg.content.clone())
+                    .and_then(content_text)
+                else {
+                    return ControlFlow::Continue(());
+                };
+                let cleaned = clean_message(&text, self.body_lines);
+                if cleaned.is_empty() {
+                    return ControlFlow::Continue(());
+                }
+                let stream_pending = self
+                    .stream_events
                     .iter()
-                    .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
-                    .collect::<Vec<_>>()
-                    .join(" "),
-                _ => continue,
-            };
+                    .any(|e| e.get("type").and_then(|t| t.as_str()) == Some("message_stop"));
+                if !stream_pending {
+                    return ControlFlow::Break(Some(cleaned));
+                }
+                self.fallback.get_or_insert(cleaned);
+            }
+            Some("stream_event") => {
+                let Some(event) = entry.event.clone() else {
+                    return ControlFlow::Continue(());
+                };
+                let is_start = event.get("type").and_then(|t| t.as_str()) == Some("message_start");
+                self.stream_events.push(event);
+                if !is_start {
+                    return ControlFlow::Continue(());
+                }
+
+                // Replay this message's events in order
+                let mut streaming = None;
+                let message = self
+                    .stream_events
+                    .drain(..)
+                    .rev()
+                    .filter_map(|event| apply_stream_event(&mut streaming, &event))
+                    .last();
+                if let Some(message) = message
+                    && message.role == self.role
+                {
+                    let cleaned = clean_message(&message.text, self.body_lines);
+                    if !cleaned.is_empty() {
+                        return ControlFlow::Break(Some(cleaned));
+                    }
+                }
+                if self.fallback.is_some() {
+                    return ControlFlow::Break(self.fallback.take());
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// The message once the start of the transcript is reached
+    fn finish(self) -> Option<String> {
+        self.fallback
+    }
+}
+
+/// Measures the last task, from the user's last prompt to the final entry.
+/// There is no duration if either has no timestamp.
+#[derive(Default)]
+struct TaskDuration {
+    end: Option<chrono::DateTime<chrono::FixedOffset>>,
+}
+
+impl TaskDuration {
+    /// Look at the next line back, breaking at the user's last prompt
+    fn feed(&mut self, entry: &TranscriptLine) -> ControlFlow<Option<u64>> {
+        let at = entry
+            .timestamp
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+        // The latest timestamped line is where the task ended
+        if self.end.is_none() {
+            self.end = at;
+        }
+
+        if is_user_prompt(entry) {
+            let ms = self
+                .end
+                .zip(at)
+                .and_then(|(end, at)| u64::try_from((end - at).num_milliseconds()).ok());
+            return ControlFlow::Break(ms);
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// A short human duration: "850ms", "42s", "2m14s" or "1h05m"
+pub fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match secs {
+        0 => format!("{}ms", ms),
+        1..60 => format!("{}s", secs),
+        60..3600 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Whether a transcript line is a prompt the user typed, rather than one of
+/// the tool results that are also recorded as user lines
+fn is_user_prompt(entry: &TranscriptLine) -> bool {
+    entry.line_type.as_deref() == Some("user")
+        && entry
+            .message
+            .as_ref()
+            .and_then(|msg| msg.content.clone())
+            .and_then(content_text)
+            .is_some_and(|text| !text.trim().is_empty())
+}
+
+/// Text of a message's content, which can be a string or an array of blocks
+fn content_text(content: serde_json::Value) -> Option<String> {
+    match content {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Array(arr) => Some(
+            arr.iter()
+                .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
 
-            let cleaned = text.lines().next().unwrap_or(&text).trim().to_string();
+/// The first `body_lines` lines of a message, trimmed and joined, skipping blank ones
+fn clean_message(text: &str, body_lines: usize) -> String {
+    text.lines()
+        .take(body_lines.max(1))
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
 
-            if !cleaned.is_empty() {
-                last_user_content = Some(cleaned);
+/// Feed one stream-json event into the message being reassembled.
+///
+/// Returns the complete message once its `message_stop` event arrives.
+fn apply_stream_event(
+    streaming: &mut Option<StreamedMessage>,
+    event: &serde_json::Value,
+) -> Option<StreamedMessage> {
+    match event.get("type").and_then(|t| t.as_str())? {
+        "message_start" => {
+            let role = event
+                .get("message")
+                .and_then(|m| m.get("role"))
+                .and_then(|r| r.as_str())
+                .unwrap_or_default();
+            *streaming = Some(StreamedMessage {
+                role: role.to_string(),
+                text: String::new(),
+            });
+            None
+        }
+        "content_block_delta" => {
+            if let Some(message) = streaming.as_mut()
+                && let Some(text) = event
+                    .get("delta")
+                    .and_then(|d| d.get("text"))
+                    .and_then(|t| t.as_str())
+            {
+                message.text.push_str(text);
             }
+            None
+        }
+        "message_stop" => streaming.take(),
+        _ => None,
+    }
+}
+
+/// Look at the next line back for the first line of the last tool result,
+/// breaking with None at the user's last prompt since results from before it
+/// belong to an earlier task
+fn last_tool_result(entry: &TranscriptLine) -> ControlFlow<Option<String>> {
+    if is_user_prompt(entry) {
+        return ControlFlow::Break(None);
+    }
+
+    let Some(serde_json::Value::Array(items)) =
+        entry.message.as_ref().and_then(|msg| msg.content.as_ref())
+    else {
+        return ControlFlow::Continue(());
+    };
+    for item in items
+        .iter()
+        .rev()
+        .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("tool_result"))
+    {
+        // Result content can be a string or an array of text blocks
+        let text = match item.get("content") {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Array(blocks)) => blocks
+                .iter()
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => continue,
+        };
+
+        if let Some(summary) = text.lines().map(str::trim).find(|l| !l.is_empty()) {
+            return ControlFlow::Break(Some(summary.to_string()));
         }
     }
+    ControlFlow::Continue(())
+}
 
-    last_user_content.ok_or_else(|| anyhow::anyhow!("No user message found in transcript"))
+/// Deliver a notification the way `ahoy send` does once it has decided to
+/// show it, but without quiet hours, cooldown, throttling or rules. The
+/// delivery is recorded in the history.
+pub fn deliver(notification: &Notification, config: &Config) -> Result<()> {
+    send_notification(
+        notification,
+        config,
+        config.local_backend.backend().as_ref(),
+        None,
+        false,
+    )?;
+    if let Err(e) = history::record(notification, config) {
+        warn!("Could not append to notification log: {:#}", e);
+    }
+    Ok(())
 }
 
-fn send_notification(notification: &Notification) -> Result<()> {
+/// Show the notification with the `local` backend and send it to any
+/// configured remote backends, plus `extra` if a rule asked for it. Remote
+/// requests run alongside the local notification rather than before it.
+/// Delivery succeeds when the local notification was shown; a remote failure
+/// is only logged unless `exit_on_failure` is set.
+fn send_notification(
+    notification: &Notification,
+    config: &Config,
+    local: &dyn NotificationBackend,
+    extra: Option<&Forward>,
+    exit_on_failure: bool,
+) -> Result<()> {
     info!("Showing notification: {:?}", notification);
-    notify::show(notification)
+
+    let mut backends = remote::backends(config, notification);
+    backends.extend(
+        extra
+            .filter(|f| f.accepts(notification))
+            .map(Backend::Forward),
+    );
+    if backends.is_empty() {
+        return local.deliver(notification);
+    }
+
+    let (local, remote_results) = std::thread::scope(|scope| {
+        let handles: Vec<_> = backends
+            .iter()
+            .map(|backend| scope.spawn(|| backend.send(notification)))
+            .collect();
+        let local = local.deliver(notification);
+        let results: Vec<_> = handles
+            .into_iter()
+            .map(|h| h.join().expect("remote delivery thread panicked"))
+            .collect();
+        (local, results)
+    });
+
+    let mut failed = Vec::new();
+    for (backend, result) in backends.iter().zip(remote_results) {
+        if let Err(e) = result {
+            warn!("Could not send notification to {}: {:#}", backend.name(), e);
+            failed.push(backend.name());
+        }
+    }
+
+    local?;
+    if exit_on_failure && !failed.is_empty() {
+        bail!("Could not send notification to {}", failed.join(", "));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    fn extract_last_message(
+        transcript_path: &str,
+        source: SummarySource,
+        body_lines: usize,
+    ) -> Result<String> {
+        let file = File::open(transcript_path)?;
+        summarize_transcript(file, source.role(), body_lines, false)?
+            .message
+            .ok_or_else(|| anyhow::anyhow!("No {} message found in transcript", source.role()))
+    }
+
+    fn summarize(transcript: impl Into<Vec<u8>>, include_tool_result: bool) -> TaskSummary {
+        summarize_transcript(
+            std::io::Cursor::new(transcript.into()),
+            "assistant",
+            1,
+            include_tool_result,
+        )
+        .unwrap()
+    }
+
     #[test]
-    fn test_extract_last_prompt_simple_string() {
+    fn test_extract_last_message_simple_string() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(
             file,
@@ -189,12 +1286,13 @@ mod tests {
         )
         .unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Fix the bug");
     }
 
     #[test]
-    fn test_extract_last_prompt_multiple_messages_returns_last() {
+    fn test_extract_last_message_multiple_messages_returns_last() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(
             file,
@@ -212,21 +1310,23 @@ mod tests {
         )
         .unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Second message");
     }
 
     #[test]
-    fn test_extract_last_prompt_array_content() {
+    fn test_extract_last_message_array_content() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, r#"{{"type":"user","message":{{"content":[{{"text":"First part"}},{{"text":"Second part"}}]}}}}"#).unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "First part Second part");
     }
 
     #[test]
-    fn test_extract_last_prompt_multiline_takes_first_line() {
+    fn test_extract_last_message_multiline_takes_first_line() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(
             file,
@@ -234,15 +1334,44 @@ mod tests {
         )
         .unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "First line");
     }
 
     #[test]
-    fn test_extract_last_prompt_empty_file() {
+    fn test_extract_last_message_body_lines_joins_lines() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","message":{{"content":"First line\nSecond line\nThird line"}}}}"#
+        )
+        .unwrap();
+
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 2).unwrap();
+        assert_eq!(result, "First line / Second line");
+    }
+
+    #[test]
+    fn test_extract_last_message_body_lines_skips_blank_lines() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","message":{{"content":"First line\n\nThird line"}}}}"#
+        )
+        .unwrap();
+
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 3).unwrap();
+        assert_eq!(result, "First line / Third line");
+    }
+
+    #[test]
+    fn test_extract_last_message_empty_file() {
         let file = NamedTempFile::new().unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap());
+        let result = extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1);
         assert!(result.is_err());
         assert!(
             result
@@ -253,7 +1382,7 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_last_prompt_no_user_messages() {
+    fn test_extract_last_message_no_user_messages() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(
             file,
@@ -266,7 +1395,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap());
+        let result = extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1);
         assert!(result.is_err());
         assert!(
             result
@@ -277,7 +1406,7 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_last_prompt_invalid_json_skipped() {
+    fn test_extract_last_message_invalid_json_skipped() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, "invalid json line").unwrap();
         writeln!(
@@ -287,12 +1416,13 @@ mod tests {
         .unwrap();
         writeln!(file, "another invalid line").unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Valid message");
     }
 
     #[test]
-    fn test_extract_last_prompt_whitespace_only_skipped() {
+    fn test_extract_last_message_whitespace_only_skipped() {
         let mut file = NamedTempFile::new().unwrap();
         writeln!(file, r#"{{"type":"user","message":{{"content":"   "}}}}"#).unwrap();
         writeln!(
@@ -301,63 +1431,526 @@ mod tests {
         )
         .unwrap();
 
-        let result = extract_last_prompt(file.path().to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Real message");
     }
 
     #[test]
-    fn test_extract_last_prompt_missing_file() {
-        let result = extract_last_prompt("/nonexistent/file.jsonl");
+    fn test_extract_last_message_missing_file() {
+        let result = extract_last_message("/nonexistent/file.jsonl", SummarySource::User, 1);
         assert!(result.is_err());
     }
 
     #[test]
-    fn test_extract_last_prompt_with_fixture() {
+    fn test_extract_last_message_with_fixture() {
         // Test with the simple fixture we created
         let fixture_path = std::env::current_dir()
             .unwrap()
             .join("tests/fixtures/transcripts/simple.jsonl");
 
-        let result = extract_last_prompt(fixture_path.to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(fixture_path.to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Write a test for it");
     }
 
     #[test]
-    fn test_extract_last_prompt_array_fixture() {
+    fn test_extract_last_message_array_fixture() {
         let fixture_path = std::env::current_dir()
             .unwrap()
             .join("tests/fixtures/transcripts/array_content.jsonl");
 
-        let result = extract_last_prompt(fixture_path.to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(fixture_path.to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "Please review this code");
     }
 
     #[test]
-    fn test_extract_last_prompt_multiline_fixture() {
+    fn test_extract_last_message_multiline_fixture() {
         let fixture_path = std::env::current_dir()
             .unwrap()
             .join("tests/fixtures/transcripts/multiline.jsonl");
 
-        let result = extract_last_prompt(fixture_path.to_str().unwrap()).unwrap();
+        let result =
+            extract_last_message(fixture_path.to_str().unwrap(), SummarySource::User, 1).unwrap();
         assert_eq!(result, "First line");
     }
 
     #[test]
-    fn test_extract_last_prompt_empty_fixture() {
+    fn test_extract_last_message_empty_fixture() {
         let fixture_path = std::env::current_dir()
             .unwrap()
             .join("tests/fixtures/transcripts/empty.jsonl");
 
-        let result = extract_last_prompt(fixture_path.to_str().unwrap());
+        let result = extract_last_message(fixture_path.to_str().unwrap(), SummarySource::User, 1);
         assert!(result.is_err());
     }
 
-    // ========== build_from_claude_stdin_reader tests ==========
+    #[test]
+    fn test_build_notification_config_defaults() {
+        let config = Config {
+            default_title: Some("Build bot".to_string()),
+            default_icon: Some("robot".to_string()),
+            ..Default::default()
+        };
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, "Build bot");
+        assert_eq!(notification.icon.as_deref(), Some("robot"));
+    }
+
+    #[test]
+    fn test_build_notification_flags_override_config() {
+        let config = Config {
+            default_title: Some("Build bot".to_string()),
+            default_icon: Some("robot".to_string()),
+            ..Default::default()
+        };
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                agent: Some(Agent::Codex),
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, "Codex");
+        // No agent icons are bundled, so the configured default still applies
+        assert_eq!(notification.icon.as_deref(), Some("robot"));
+    }
+
+    #[test]
+    fn test_build_notification_icon_flag_wins_over_config() {
+        let config = Config {
+            default_icon: Some("robot".to_string()),
+            ..Default::default()
+        };
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                agent: Some(Agent::Codex),
+                icon: Some("deploy.png".to_string()),
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, "Codex");
+        assert_eq!(notification.icon.as_deref(), Some("deploy.png"));
+    }
+
+    #[test]
+    fn test_build_notification_timeout() {
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                timeout_ms: Some(0),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert!(notification.is_sticky());
+    }
+
+    #[test]
+    fn test_build_notification_replace_overrides_json() {
+        let notification = build_notification(
+            SendOptions {
+                json: Some(r#"{"title":"CI","body":"Building...","replace_id":"ci"}"#.to_string()),
+                replace_id: Some("deploy".to_string()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(notification.replace_id.as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn test_build_notification_subtitle() {
+        let build = |subtitle: &str| {
+            build_notification(
+                SendOptions {
+                    message: Some("Task finished".to_string()),
+                    subtitle: Some(subtitle.to_string()),
+                    ..Default::default()
+                },
+                &Config::default(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(build("my\napp").subtitle.as_deref(), Some("my app"));
+        assert_eq!(build("  ").subtitle, None);
+    }
+
+    #[test]
+    fn test_build_notification_config_metadata_limits() {
+        let json = r#"{"title":"Build","body":"Done","metadata":{"a":1,"b":2,"c":3}}"#;
+        let opts = SendOptions {
+            json: Some(json.to_string()),
+            ..Default::default()
+        };
+
+        assert!(build_notification(opts.clone(), &Config::default()).is_ok());
+
+        let config = Config {
+            max_metadata_keys: Some(2),
+            ..Default::default()
+        };
+        let err = build_notification(opts.clone(), &config).unwrap_err();
+        assert!(format!("{:#}", err).contains("metadata has 3 keys (max 2)"));
+
+        let config = Config {
+            max_metadata_bytes: Some(10),
+            ..Default::default()
+        };
+        assert!(build_notification(opts, &config).is_err());
+    }
+
+    #[test]
+    fn test_build_notification_config_max_body_chars() {
+        let config = Config {
+            max_body_chars: Some(20),
+            ..Default::default()
+        };
+        let payload = r#"{"type":"agent-turn-complete","cwd":"/Users/test/myproject","last-assistant-message":"Renamed load_config to config::load everywhere."}"#;
+
+        let notification = build_notification(
+            SendOptions {
+                message: Some(payload.to_string()),
+                from_codex: true,
+                ..Default::default()
+            },
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(notification.body, "[myproject] Renamed load_conf...");
+    }
+
+    #[test]
+    fn test_build_notification_without_config_uses_ahoy_title() {
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, DEFAULT_TITLE);
+        assert_eq!(notification.icon, None);
+    }
+
+    #[test]
+    fn test_build_notification_sanitizes_control_characters() {
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Fix\0 the\x1b bug\n\nnow".to_string()),
+                title: Some("Build\tdone\x07".to_string()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, "Build done");
+        assert_eq!(notification.body, "Fix the bug now");
+    }
+
+    #[test]
+    fn test_build_notification_control_only_body_uses_fallback() {
+        let notification = build_notification(
+            SendOptions {
+                message: Some("\0\x01".to_string()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(notification.body, i18n::text(Phrase::NoMessage));
+    }
+
+    #[test]
+    fn test_build_notification_empty_activate_is_none() {
+        let notification = build_notification(
+            SendOptions {
+                message: Some("Done".to_string()),
+                activate: vec![String::new()],
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert!(notification.activate.is_empty());
+    }
+
+    #[test]
+    fn test_build_notification_empty_activate_keeps_json_value() {
+        let notification = build_notification(
+            SendOptions {
+                json: Some(
+                    r#"{"title":"T","body":"B","activate":"com.apple.Terminal"}"#.to_string(),
+                ),
+                activate: vec![String::new()],
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            notification.activate,
+            vec!["com.apple.Terminal".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_build_notification_empty_activate_in_json_is_none() {
+        let notification = build_notification(
+            SendOptions {
+                json: Some(r#"{"title":"T","body":"B","activate":""}"#.to_string()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert!(notification.activate.is_empty());
+    }
+
+    #[test]
+    fn test_build_notification_activate_fallbacks_override_json() {
+        let notification = build_notification(
+            SendOptions {
+                json: Some(
+                    r#"{"title":"T","body":"B","activate":"com.apple.Terminal"}"#.to_string(),
+                ),
+                activate: vec![
+                    "com.mitchellh.ghostty".to_string(),
+                    "com.googlecode.iterm2".to_string(),
+                ],
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            notification.activate,
+            vec![
+                "com.mitchellh.ghostty".to_string(),
+                "com.googlecode.iterm2".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fill_empty_fields_title() {
+        let mut notification = Notification::new("", "Body");
+        fill_empty_fields(&mut notification, DEFAULT_TITLE);
+        assert_eq!(notification.title, DEFAULT_TITLE);
+        assert_eq!(notification.body, "Body");
+    }
+
+    #[test]
+    fn test_fill_empty_fields_body() {
+        let mut notification = Notification::new("Title", "  ");
+        fill_empty_fields(&mut notification, DEFAULT_TITLE);
+        assert_eq!(notification.title, "Title");
+        assert_eq!(notification.body, "(no message)");
+    }
+
+    #[test]
+    fn test_fill_empty_fields_leaves_content_alone() {
+        let mut notification = Notification::new("Title", "Body");
+        fill_empty_fields(&mut notification, DEFAULT_TITLE);
+        assert_eq!(notification.title, "Title");
+        assert_eq!(notification.body, "Body");
+    }
+
+    #[test]
+    fn test_exit_title() {
+        assert_eq!(exit_title(0), "Succeeded");
+        assert_eq!(exit_title(2), "Failed (exit 2)");
+    }
+
+    #[test]
+    fn test_extract_last_message_interleaved_fixture_picks_role() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/interleaved.jsonl");
+        let path = fixture_path.to_str().unwrap();
+
+        let user = extract_last_message(path, SummarySource::User, 1).unwrap();
+        assert_eq!(user, "Now run the tests");
+
+        // The final assistant entry is only a tool call, so the reply before it is used
+        let assistant = extract_last_message(path, SummarySource::Assistant, 1).unwrap();
+        assert_eq!(assistant, "All 42 tests pass.");
+    }
+
+    #[test]
+    fn test_extract_last_message_no_assistant_messages() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, r#"{{"type":"user","message":{{"content":"Hello"}}}}"#).unwrap();
+
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::Assistant, 1);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("No assistant message found")
+        );
+    }
+
+    #[test]
+    fn test_extract_last_message_stream_json_assistant() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/stream_json.jsonl");
+
+        let result =
+            extract_last_message(fixture_path.to_str().unwrap(), SummarySource::Assistant, 1)
+                .unwrap();
+        assert_eq!(result, "Done.");
+    }
+
+    #[test]
+    fn test_extract_last_message_stream_json_fixture() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/stream_json.jsonl");
+
+        let result =
+            extract_last_message(fixture_path.to_str().unwrap(), SummarySource::User, 1).unwrap();
+        assert_eq!(result, "Now add a changelog entry");
+    }
+
+    #[test]
+    fn test_extract_last_message_stream_json_incomplete_message_ignored() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"user","message":{{"content":"Whole message"}}}}"#
+        )
+        .unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"stream_event","event":{{"type":"message_start","message":{{"role":"user"}}}}}}"#
+        )
+        .unwrap();
+        writeln!(
+            file,
+            r#"{{"type":"stream_event","event":{{"type":"content_block_delta","index":0,"delta":{{"type":"text_delta","text":"Never finished"}}}}}}"#
+        )
+        .unwrap();
+
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
+        assert_eq!(result, "Whole message");
+    }
+
+    #[test]
+    fn test_extract_last_message_plain_message_inside_stream_loses_to_it() {
+        let mut file = NamedTempFile::new().unwrap();
+        for line in [
+            r#"{"type":"stream_event","event":{"type":"message_start","message":{"role":"user"}}}"#,
+            r#"{"type":"user","message":{"content":"Plain message"}}"#,
+            r#"{"type":"stream_event","event":{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Streamed message"}}}"#,
+            r#"{"type":"stream_event","event":{"type":"message_stop"}}"#,
+        ] {
+            writeln!(file, "{}", line).unwrap();
+        }
+
+        let result =
+            extract_last_message(file.path().to_str().unwrap(), SummarySource::User, 1).unwrap();
+        assert_eq!(result, "Streamed message");
+    }
+
+    /// Reader that counts how many bytes were read through it
+    struct CountingReader<R> {
+        inner: R,
+        bytes_read: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.bytes_read.set(self.bytes_read.get() + n);
+            Ok(n)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_summarize_reads_only_the_end_of_large_transcripts() {
+        let mut transcript = String::new();
+        for i in 0..50_000 {
+            transcript.push_str(&format!(
+                "{{\"type\":\"user\",\"message\":{{\"content\":\"Prompt {}\"}}}}\n",
+                i
+            ));
+            transcript.push_str(&format!(
+                "{{\"type\":\"assistant\",\"message\":{{\"content\":\"Reply {}\"}}}}\n",
+                i
+            ));
+        }
+        assert!(transcript.len() > 4_000_000);
+
+        let bytes_read = std::rc::Rc::new(std::cell::Cell::new(0));
+        let reader = CountingReader {
+            inner: std::io::Cursor::new(transcript.into_bytes()),
+            bytes_read: bytes_read.clone(),
+        };
+
+        // The message, tool result and duration all come from one pass that
+        // stops at the last prompt
+        let task = summarize_transcript(reader, "assistant", 1, true).unwrap();
+        assert_eq!(task.message.as_deref(), Some("Reply 49999"));
+        assert_eq!(task.tool_result, None);
+        assert!(
+            bytes_read.get() <= 16 * 1024,
+            "read {} bytes",
+            bytes_read.get()
+        );
+    }
+
+    // ========== build_from_hook_reader tests ==========
 
     #[test]
     fn test_build_from_stdin_empty() {
         let mock_stdin = std::io::Cursor::new("");
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.title, "Test");
         assert_eq!(result.body, "Task finished");
@@ -366,7 +1959,12 @@ mod tests {
     #[test]
     fn test_build_from_stdin_invalid_json() {
         let mock_stdin = std::io::Cursor::new("not valid json");
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test");
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        );
 
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("parse"));
@@ -380,7 +1978,13 @@ mod tests {
             "tool_input": {"command": "npm install"}
         }"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Claude Code").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Claude Code",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.title, "Claude Code");
         assert_eq!(result.body, "[myproject] Bash: npm install");
@@ -394,7 +1998,13 @@ mod tests {
             "tool_input": {"file_path": "/path/to/file.rs"}
         }"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Claude Code").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Claude Code",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.body, "[myproject] Read: /path/to/file.rs");
     }
@@ -407,7 +2017,13 @@ mod tests {
             "tool_input": {"pattern": "TODO"}
         }"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Claude Code").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Claude Code",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.body, "[myproject] Grep: TODO");
     }
@@ -419,11 +2035,42 @@ mod tests {
             "tool_name": "Bash"
         }"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Claude Code").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Claude Code",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.body, "[myproject] Needs permission: Bash");
     }
 
+    #[test]
+    fn test_build_from_stdin_template_from_transcript() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/interleaved.jsonl");
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            fixture_path.to_str().unwrap()
+        );
+        let options = HookOptions {
+            template: Some("Done in {project}: {prompt}{tool}".to_string()),
+            ..Default::default()
+        };
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Claude Code",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(result.body, "Done in myproject: Now run the tests");
+    }
+
     #[test]
     fn test_build_from_stdin_tool_truncation_at_60_chars() {
         // Create a command that's exactly 61 chars (should truncate)
@@ -437,7 +2084,13 @@ mod tests {
             long_command
         );
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         // Should be truncated to 57 chars + "..."
         assert!(result.body.contains("..."));
@@ -458,16 +2111,74 @@ mod tests {
             command
         );
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert!(!result.body.contains("..."));
     }
 
+    #[test]
+    fn test_build_from_stdin_max_tool_chars() {
+        let json = r#"{"cwd": "/Users/test/myproject", "tool_name": "Bash", "tool_input": {"command": "cargo test --workspace"}}"#;
+        let options = HookOptions {
+            max_tool_chars: 10,
+            ..Default::default()
+        };
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Claude Code",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(result.body, "[myproject] Bash: cargo t...");
+    }
+
+    #[test]
+    fn test_build_from_stdin_tool_truncation_multibyte() {
+        // Byte index 57 falls inside a character here, which used to panic
+        let command = "日本語のテストコマンド".repeat(10);
+        let json = format!(
+            r#"{{
+            "cwd": "/Users/test/myproject",
+            "tool_name": "Bash",
+            "tool_input": {{"command": "{}"}}
+        }}"#,
+            command
+        );
+        let mock_stdin = std::io::Cursor::new(json);
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
+
+        let command_part = result.body.split(": ").nth(1).unwrap();
+        assert_eq!(command_part.chars().count(), 60);
+        assert!(command_part.starts_with("日本語のテストコマンド"));
+        assert!(command_part.ends_with("..."));
+    }
+
     #[test]
     fn test_build_from_stdin_project_name_extraction() {
         let json = r#"{"cwd": "/home/user/projects/awesome-app"}"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert!(result.body.starts_with("[awesome-app]"));
     }
@@ -476,7 +2187,13 @@ mod tests {
     fn test_build_from_stdin_project_name_no_cwd() {
         let json = r#"{}"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert!(result.body.starts_with("[project]"));
     }
@@ -485,7 +2202,13 @@ mod tests {
     fn test_build_from_stdin_project_name_trailing_slash() {
         let json = r#"{"cwd": "/home/user/myproject/"}"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         // Trailing slash results in empty string, falls back to "project"
         assert!(result.body.starts_with("[]") || result.body.starts_with("[project]"));
@@ -510,16 +2233,679 @@ mod tests {
         );
 
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Claude Code").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Claude Code",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.body, "[myproject] Deploy to production");
     }
 
     #[test]
+    fn test_build_from_codex_payload_last_assistant_message() {
+        let payload = r#"{"type":"agent-turn-complete","turn-id":"1","cwd":"/Users/test/myproject","input-messages":["Rename the module"],"last-assistant-message":"Renamed foo to bar.\nAll tests pass."}"#;
+
+        let result = build_from_codex_payload(payload, "Codex", &HookOptions::default()).unwrap();
+
+        assert_eq!(result.title, "Codex");
+        assert_eq!(result.body, "[myproject] Renamed foo to bar.");
+    }
+
+    #[test]
+    fn test_build_from_codex_payload_falls_back_to_input_message() {
+        let payload =
+            r#"{"type":"agent-turn-complete","input-messages":["First","Fix the flaky test"]}"#;
+
+        let result = build_from_codex_payload(payload, "Codex", &HookOptions::default()).unwrap();
+
+        assert_eq!(result.body, "Fix the flaky test");
+    }
+
+    #[test]
+    fn test_build_from_codex_payload_unknown_event() {
+        let payload = r#"{"type":"something-else","last-assistant-message":"Hi"}"#;
+
+        let result = build_from_codex_payload(payload, "Codex", &HookOptions::default()).unwrap();
+
+        assert_eq!(result.body, "Task finished");
+    }
+
+    #[test]
+    fn test_build_from_codex_payload_truncates_by_chars() {
+        let message = "é".repeat(150);
+        let payload = format!(
+            r#"{{"type":"agent-turn-complete","last-assistant-message":"{}"}}"#,
+            message
+        );
+
+        let result = build_from_codex_payload(&payload, "Codex", &HookOptions::default()).unwrap();
+
+        assert_eq!(result.body.chars().count(), 100);
+        assert!(result.body.ends_with("..."));
+    }
+
+    #[test]
+    fn test_build_from_codex_payload_invalid_json() {
+        assert!(build_from_codex_payload("not json", "Codex", &HookOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_read_json_stdin_full_document() {
+        let json = r#"{
+            "title": "Deploy",
+            "body": "Waiting for approval",
+            "icon": "codex",
+            "urgency": "critical",
+            "url": "https://ci.example.com/42",
+            "metadata": {"severity": "error"}
+        }"#;
+
+        let notification = build_notification(
+            SendOptions {
+                json: Some(read_json_stdin(std::io::Cursor::new(json)).unwrap()),
+                ..Default::default()
+            },
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(notification.title, "Deploy");
+        assert_eq!(notification.body, "Waiting for approval");
+        assert_eq!(notification.icon.as_deref(), Some("codex"));
+        assert_eq!(notification.urgency, Some(Urgency::Critical));
+        assert_eq!(
+            notification.url.as_deref(),
+            Some("https://ci.example.com/42")
+        );
+        assert_eq!(notification.metadata["severity"], "error");
+    }
+
+    #[test]
+    fn test_read_json_stdin_invalid_utf8() {
+        let err = read_json_stdin(std::io::Cursor::new(b"\xff\xfe".to_vec())).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to read notification JSON from stdin"
+        );
+    }
+
+    /// Records what it's asked to deliver, or fails every delivery
+    #[derive(Default)]
+    struct RecordingBackend {
+        delivered: std::sync::Mutex<Vec<Notification>>,
+        fail: bool,
+    }
+
+    impl NotificationBackend for RecordingBackend {
+        fn deliver(&self, notification: &Notification) -> Result<()> {
+            if self.fail {
+                bail!("notifier unavailable");
+            }
+            self.delivered.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_send_notification_delivers_to_local_backend() {
+        let local = RecordingBackend::default();
+        let notification = Notification::new("Claude Code", "[app] Task finished");
+
+        send_notification(&notification, &Config::default(), &local, None, false).unwrap();
+
+        let delivered = local.delivered.lock().unwrap();
+        assert_eq!(delivered.len(), 1);
+        assert_eq!(delivered[0].body, "[app] Task finished");
+    }
+
+    /// A forward destination nothing listens on: a port the OS just handed
+    /// out and released
+    fn unreachable_forward() -> Forward {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        toml::from_str(&format!("url = \"http://127.0.0.1:{}\"", port)).unwrap()
+    }
+
+    #[test]
+    fn test_send_notification_fails_when_local_delivery_fails() {
+        let local = RecordingBackend {
+            fail: true,
+            ..Default::default()
+        };
+        let notification = Notification::new("Claude Code", "[app] Task finished");
+
+        let err =
+            send_notification(&notification, &Config::default(), &local, None, false).unwrap_err();
+        assert_eq!(err.to_string(), "notifier unavailable");
+
+        let forward = unreachable_forward();
+        let err = send_notification(
+            &notification,
+            &Config::default(),
+            &local,
+            Some(&forward),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "notifier unavailable");
+    }
+
+    #[test]
+    fn test_send_notification_remote_failure_is_best_effort_by_default() {
+        let local = RecordingBackend::default();
+        let notification = Notification::new("Claude Code", "[app] Task finished");
+        let forward = unreachable_forward();
+
+        send_notification(
+            &notification,
+            &Config::default(),
+            &local,
+            Some(&forward),
+            false,
+        )
+        .unwrap();
+        assert_eq!(local.delivered.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_send_notification_exit_on_failure_fails_on_remote_failure() {
+        let local = RecordingBackend::default();
+        let notification = Notification::new("Claude Code", "[app] Task finished");
+        let forward = unreachable_forward();
+
+        let err = send_notification(
+            &notification,
+            &Config::default(),
+            &local,
+            Some(&forward),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Could not send notification to webhook");
+        assert_eq!(local.delivered.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_batch_items() {
+        let items = batch_items(
+            r#"[{"title": "A", "body": "one"}, {"title": "B"}, {"title": "C", "body": "three"}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(items.len(), 3);
+        let first: Notification = serde_json::from_str(&items[0]).unwrap();
+        assert_eq!(first.body, "one");
+        // Each entry parses on its own, so a bad one doesn't spoil the rest
+        assert!(serde_json::from_str::<Notification>(&items[1]).is_err());
+
+        assert!(batch_items(r#"{"title": "A", "body": "one"}"#).is_none());
+        assert!(batch_items("not json").is_none());
+    }
+
+    #[test]
+    fn test_build_from_codex_payload_template() {
+        let payload = r#"{"type":"agent-turn-complete","cwd":"/Users/test/myproject","input-messages":["Rename the module"],"last-assistant-message":"Renamed foo to bar."}"#;
+        let options = HookOptions {
+            template: Some("{prompt} ({cwd})".to_string()),
+            ..Default::default()
+        };
+
+        let result = build_from_codex_payload(payload, "Codex in {project}", &options).unwrap();
+
+        assert_eq!(result.title, "Codex in myproject");
+        assert_eq!(result.body, "Rename the module (/Users/test/myproject)");
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_template_for_tool() {
+        let options = HookOptions {
+            template: Some("{tool} in {project}: {message} {unknown} {{x}}".to_string()),
+            ..Default::default()
+        };
+
+        let result = build_from_hook_reader(
+            hook_fixture("gemini_before_tool.json"),
+            HookFormat::Gemini,
+            "Gemini: {tool}",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(result.title, "Gemini: run_shell_command");
+        assert_eq!(
+            result.body,
+            "run_shell_command in myproject: run_shell_command: cargo test {unknown} {x}"
+        );
+    }
+
+    fn hook_fixture(name: &str) -> std::fs::File {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts")
+            .join(name);
+        std::fs::File::open(path).unwrap()
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_codex_format() {
+        let result = build_from_hook_reader(
+            hook_fixture("codex_notify.json"),
+            HookFormat::Codex,
+            "Codex",
+            &HookOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.body,
+            "[myproject] Renamed load_config to config::load and updated the callers."
+        );
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_gemini_after_agent() {
+        let prompt = build_from_hook_reader(
+            hook_fixture("gemini_after_agent.json"),
+            HookFormat::Gemini,
+            "Gemini",
+            &HookOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(prompt.title, "Gemini");
+        assert_eq!(
+            prompt.body,
+            "[myproject] Add a --dry-run flag to the deploy script"
+        );
+
+        let options = HookOptions {
+            summary: SummarySource::Assistant,
+            body_lines: 2,
+            ..Default::default()
+        };
+        let reply = build_from_hook_reader(
+            hook_fixture("gemini_after_agent.json"),
+            HookFormat::Gemini,
+            "Gemini",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            reply.body,
+            "[myproject] Added --dry-run. / It prints each step without running it."
+        );
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_gemini_notification() {
+        let result = build_from_hook_reader(
+            hook_fixture("gemini_notification.json"),
+            HookFormat::Gemini,
+            "Gemini",
+            &HookOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result.body,
+            "[myproject] Gemini needs your permission to use run_shell_command"
+        );
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_gemini_tool() {
+        let result = build_from_hook_reader(
+            hook_fixture("gemini_before_tool.json"),
+            HookFormat::Gemini,
+            "Gemini",
+            &HookOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.body, "[myproject] run_shell_command: cargo test");
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_gemini_without_text() {
+        let payload = r#"{"cwd": "/Users/test/myproject", "hook_event_name": "AfterAgent"}"#;
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(payload),
+            HookFormat::Gemini,
+            "Gemini",
+            &HookOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Task finished");
+
+        let err = build_from_hook_reader(
+            std::io::Cursor::new(payload),
+            HookFormat::Gemini,
+            "Gemini",
+            &strict(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no message text"));
+    }
+
+    #[test]
+    fn test_build_from_hook_reader_empty_input_names_format() {
+        let err = build_from_hook_reader(
+            std::io::Cursor::new(""),
+            HookFormat::Gemini,
+            "Gemini",
+            &strict(),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "No Gemini hook data on stdin");
+    }
+
+    fn strict() -> HookOptions {
+        HookOptions {
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_from_stdin_strict_rejects_empty_input() {
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(""),
+            HookFormat::Claude,
+            "Test",
+            &strict(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_from_stdin_strict_requires_cwd() {
+        let json = r#"{"tool_name": "Bash"}"#;
+        let err = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &strict(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("cwd"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_build_from_stdin_strict_requires_tool_or_transcript() {
+        let json = r#"{"cwd": "/Users/test/myproject"}"#;
+        let err = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &strict(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("transcript_path"));
+
+        // Lenient mode still falls back to the generic message
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Task finished");
+    }
+
+    #[test]
+    fn test_build_from_stdin_strict_rejects_unreadable_transcript() {
+        let json = r#"{"cwd": "/Users/test/myproject", "transcript_path": "/nonexistent/t.jsonl"}"#;
+        let err = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &strict(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/t.jsonl"));
+    }
+
+    #[test]
+    fn test_build_from_stdin_strict_accepts_complete_data() {
+        let json = r#"{"cwd": "/Users/test/myproject", "tool_name": "Bash", "tool_input": {"command": "ls"}}"#;
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &strict(),
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Bash: ls");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0ms");
+        assert_eq!(format_duration(850), "850ms");
+        assert_eq!(format_duration(42_300), "42s");
+        assert_eq!(format_duration(65_000), "1m05s");
+        assert_eq!(format_duration(134_000), "2m14s");
+        assert_eq!(format_duration(3_600_000), "1h00m");
+        assert_eq!(format_duration(3_959_999), "1h05m");
+        assert_eq!(format_duration(26 * 3_600_000), "26h00m");
+    }
+
+    #[test]
+    fn test_transcript_duration_from_last_prompt() {
+        let transcript = std::fs::read(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/fixtures/transcripts/timestamps.jsonl"),
+        )
+        .unwrap();
+
+        // The tool result is a user line too, but the task began at the prompt
+        assert_eq!(summarize(transcript, false).duration_ms, Some(134_900));
+    }
+
+    #[test]
+    fn test_transcript_duration_without_timestamps() {
+        let transcript = r#"{"type":"user","message":{"content":"Fix it"}}"#;
+
+        assert_eq!(summarize(transcript, false).duration_ms, None);
+    }
+
+    #[test]
+    fn test_build_from_stdin_task_finished_includes_duration() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"Run the migrations"}},"timestamp":"2026-03-01T09:00:00.000Z"}}
+{{"type":"assistant","message":{{"content":[{{"type":"tool_use","name":"Bash","input":{{}}}}]}},"timestamp":"2026-03-01T09:02:14.400Z"}}"#
+        )
+        .unwrap();
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            transcript.path().display()
+        );
+        // The agent only made tool calls, so there is no reply to show
+        let options = HookOptions {
+            summary: SummarySource::Assistant,
+            ..Default::default()
+        };
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json.clone()),
+            HookFormat::Claude,
+            "Test",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Done in 2m14s");
+
+        let options = HookOptions {
+            template: Some("{prompt} ({duration})".to_string()),
+            ..Default::default()
+        };
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(result.body, "Run the migrations (2m14s)");
+    }
+
+    #[test]
+    fn test_build_from_stdin_duration_replaces_summary() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"Run the migrations"}},"timestamp":"2026-03-01T09:00:00.000Z"}}
+{{"type":"assistant","message":{{"content":"Migrations applied"}},"timestamp":"2026-03-01T09:02:14.400Z"}}"#
+        )
+        .unwrap();
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            transcript.path().display()
+        );
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json.clone()),
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Done in 2m14s");
+
+        // The summary is still there for templates
+        let options = HookOptions {
+            template: Some("[{project}] {prompt} — {duration}".to_string()),
+            ..Default::default()
+        };
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Run the migrations — 2m14s");
+    }
+
+    #[test]
+    fn test_build_from_stdin_task_finished_without_timestamps() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"Run the migrations"}}}}"#
+        )
+        .unwrap();
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            transcript.path().display()
+        );
+        let options = HookOptions {
+            summary: SummarySource::Assistant,
+            ..Default::default()
+        };
+
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] Task finished");
+    }
+
+    #[test]
+    #[serial]
+    fn test_build_from_stdin_transcript_from_env() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"Run the migrations"}}}}"#
+        )
+        .unwrap();
+
+        unsafe {
+            std::env::set_var(TRANSCRIPT_ENV, transcript.path());
+        }
+        let json = r#"{"cwd": "/Users/test/myproject"}"#;
+        let mock_stdin = std::io::Cursor::new(json);
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        );
+        unsafe {
+            std::env::remove_var(TRANSCRIPT_ENV);
+        }
+
+        assert_eq!(result.unwrap().body, "[myproject] Run the migrations");
+    }
+
+    #[test]
+    #[serial]
+    fn test_build_from_stdin_transcript_path_overrides_env() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"From stdin"}}}}"#
+        )
+        .unwrap();
+        let mut env_transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            env_transcript,
+            r#"{{"type":"user","message":{{"content":"From env"}}}}"#
+        )
+        .unwrap();
+
+        unsafe {
+            std::env::set_var(TRANSCRIPT_ENV, env_transcript.path());
+        }
+        let json = format!(
+            r#"{{
+            "cwd": "/Users/test/myproject",
+            "transcript_path": "{}"
+        }}"#,
+            transcript.path().to_str().unwrap()
+        );
+        let mock_stdin = std::io::Cursor::new(json);
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        );
+        unsafe {
+            std::env::remove_var(TRANSCRIPT_ENV);
+        }
+
+        assert_eq!(result.unwrap().body, "[myproject] From stdin");
+    }
+
+    #[test]
+    #[serial]
     fn test_build_from_stdin_stop_hook_no_transcript() {
         let json = r#"{"cwd": "/Users/test/myproject"}"#;
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(result.body, "[myproject] Task finished");
     }
@@ -545,7 +2931,13 @@ mod tests {
         );
 
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         // Should be truncated to 97 chars + "..."
         assert!(result.body.contains("..."));
@@ -553,6 +2945,39 @@ mod tests {
         assert_eq!(prompt_part.len(), 100); // 97 + "..."
     }
 
+    #[test]
+    fn test_build_from_stdin_prompt_truncation_emoji_at_cutoff() {
+        // The 4-byte emoji straddles byte 97, which used to panic
+        let mut transcript = NamedTempFile::new().unwrap();
+        let prompt = format!("{}🚀{}", "a".repeat(95), "b".repeat(10));
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"{}"}}}}"#,
+            prompt
+        )
+        .unwrap();
+
+        let json = format!(
+            r#"{{
+            "cwd": "/Users/test/myproject",
+            "transcript_path": "{}"
+        }}"#,
+            transcript.path().to_str().unwrap()
+        );
+
+        let mock_stdin = std::io::Cursor::new(json);
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
+
+        let prompt_part = result.body.split("] ").nth(1).unwrap();
+        assert_eq!(prompt_part, format!("{}🚀b...", "a".repeat(95)));
+    }
+
     #[test]
     fn test_build_from_stdin_prompt_no_truncation_at_100_chars() {
         // Prompt exactly 100 chars should NOT truncate
@@ -574,8 +2999,195 @@ mod tests {
         );
 
         let mock_stdin = std::io::Cursor::new(json);
-        let result = build_from_claude_stdin_reader(mock_stdin, "Test").unwrap();
+        let result = build_from_hook_reader(
+            mock_stdin,
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
 
         assert!(!result.body.contains("..."));
     }
+
+    #[test]
+    fn test_build_from_stdin_summary_assistant() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/interleaved.jsonl");
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            fixture_path.to_str().unwrap()
+        );
+
+        let default = build_from_hook_reader(
+            std::io::Cursor::new(json.clone()),
+            HookFormat::Claude,
+            "Test",
+            &HookOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(default.body, "[myproject] Now run the tests");
+
+        let options = HookOptions {
+            summary: SummarySource::Assistant,
+            body_lines: 2,
+            ..Default::default()
+        };
+        let result = build_from_hook_reader(
+            std::io::Cursor::new(json),
+            HookFormat::Claude,
+            "Test",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(result.body, "[myproject] All 42 tests pass.");
+    }
+
+    #[test]
+    fn test_build_from_stdin_summary_only_uses_first_sentence() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        let prompt = format!(
+            "Please refactor the config loader. {}",
+            "Then update every caller and the docs. ".repeat(5)
+        );
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"{}"}}}}"#,
+            prompt
+        )
+        .unwrap();
+
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            transcript.path().to_str().unwrap()
+        );
+        let options = HookOptions {
+            summary_only: true,
+            ..Default::default()
+        };
+        let mock_stdin = std::io::Cursor::new(json);
+        let result =
+            build_from_hook_reader(mock_stdin, HookFormat::Claude, "Test", &options).unwrap();
+
+        assert_eq!(
+            result.body,
+            "[myproject] Please refactor the config loader."
+        );
+    }
+
+    #[test]
+    fn test_build_from_stdin_summary_only_falls_back_to_truncation() {
+        let mut transcript = NamedTempFile::new().unwrap();
+        writeln!(
+            transcript,
+            r#"{{"type":"user","message":{{"content":"{}"}}}}"#,
+            "a".repeat(101)
+        )
+        .unwrap();
+
+        let json = format!(
+            r#"{{"cwd": "/Users/test/myproject", "transcript_path": "{}"}}"#,
+            transcript.path().to_str().unwrap()
+        );
+        let options = HookOptions {
+            summary_only: true,
+            ..Default::default()
+        };
+        let mock_stdin = std::io::Cursor::new(json);
+        let result =
+            build_from_hook_reader(mock_stdin, HookFormat::Claude, "Test", &options).unwrap();
+
+        assert!(result.body.ends_with("..."));
+    }
+
+    // ========== tool result tests ==========
+
+    #[test]
+    fn test_extract_last_tool_result_fixture() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/tool_result.jsonl");
+
+        let task = summarize(std::fs::read(fixture_path).unwrap(), true);
+        assert_eq!(
+            task.tool_result.as_deref(),
+            Some("test result: ok. 12 passed; 0 failed")
+        );
+    }
+
+    #[test]
+    fn test_extract_last_tool_result_none() {
+        let fixture_path = std::env::current_dir()
+            .unwrap()
+            .join("tests/fixtures/transcripts/simple.jsonl");
+
+        let task = summarize(std::fs::read(fixture_path).unwrap(), true);
+        assert_eq!(task.tool_result, None);
+    }
+
+    #[test]
+    fn test_last_tool_result_ignores_results_before_last_prompt() {
+        let transcript = [
+            r#"{"type":"user","message":{"content":"Run the tests"}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","tool_use_id":"toolu_01","content":"3 failed"}]}}"#,
+            r#"{"type":"user","message":{"content":"Now just explain the failures"}}"#,
+            r#"{"type":"assistant","message":{"content":"They time out on CI."}}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(summarize(transcript, true).tool_result, None);
+    }
+
+    #[test]
+    fn test_last_tool_result_takes_last_block_of_last_result() {
+        let transcript = [
+            r#"{"type":"user","message":{"content":"Run the tests"}}"#,
+            r#"{"type":"user","message":{"content":[{"type":"tool_result","content":"build ok"},{"type":"tool_result","content":"\n12 passed"}]}}"#,
+            r#"{"type":"assistant","message":{"content":"All good."}}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(
+            summarize(transcript, true).tool_result.as_deref(),
+            Some("12 passed")
+        );
+    }
+
+    #[test]
+    fn test_build_from_stdin_include_tool_result() {
+        let json = r#"{
+            "cwd": "/Users/test/myproject",
+            "transcript_path": "tests/fixtures/transcripts/tool_result.jsonl"
+        }"#;
+        let options = HookOptions {
+            include_tool_result: true,
+            ..Default::default()
+        };
+        let mock_stdin = std::io::Cursor::new(json);
+        let result =
+            build_from_hook_reader(mock_stdin, HookFormat::Claude, "Test", &options).unwrap();
+
+        assert_eq!(
+            result.body,
+            "[myproject] test result: ok. 12 passed; 0 failed"
+        );
+    }
+
+    #[test]
+    fn test_build_from_stdin_include_tool_result_falls_back_to_prompt() {
+        let json = r#"{
+            "cwd": "/Users/test/myproject",
+            "transcript_path": "tests/fixtures/transcripts/simple.jsonl"
+        }"#;
+        let options = HookOptions {
+            include_tool_result: true,
+            ..Default::default()
+        };
+        let mock_stdin = std::io::Cursor::new(json);
+        let result =
+            build_from_hook_reader(mock_stdin, HookFormat::Claude, "Test", &options).unwrap();
+
+        assert_eq!(result.body, "[myproject] Write a test for it");
+    }
 }
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+/// Strip common inline Markdown so agent text reads cleanly in a banner.
+///
+/// Removes emphasis markers (`*`, `_`, `~~`), unwraps inline code and links,
+/// and drops leading heading markers. Markers inside words (e.g. `snake_case`)
+/// and standalone operators (e.g. `2 * 3`) are left alone.
+pub fn strip_markdown(text: &str) -> String {
+    text.lines()
+        .map(|line| strip_inline(strip_heading(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Shorten text to at most `max_chars` characters, ending in "..." if it was cut.
+/// Limits too small to fit any text before the "..." just cut the text.
+///
+/// Counts characters rather than bytes, so multibyte text never splits mid-character.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars <= 3 {
+        return text.chars().take(max_chars).collect();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept)
+}
+
+/// Summarize long text as its first sentence, if that sentence fits in `max_chars`.
+///
+/// Returns None when there's no sentence boundary within the limit, so callers
+/// can fall back to plain truncation.
+pub fn first_sentence(text: &str, max_chars: usize) -> Option<&str> {
+    let mut chars = text.char_indices().peekable();
+    let mut count = 0;
+
+    while let Some((i, c)) = chars.next() {
+        count += 1;
+        if count > max_chars {
+            return None;
+        }
+        let at_boundary = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_boundary {
+            let sentence = text[..i + c.len_utf8()].trim();
+            // Skip trivially short fragments like "Ok." or "1."
+            return (sentence.split_whitespace().count() >= 3).then_some(sentence);
+        }
+    }
+
+    None
+}
+
+/// Fill `{name}` placeholders from `vars`.
+///
+/// Unknown placeholders are left as written, and `{{` and `}}` produce literal
+/// braces. Substituted values are not themselves expanded.
+pub fn render_template(template: &str, vars: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+        } else if let Some(end) = tail.find('}')
+            && tail.starts_with('{')
+            && let Some(value) = vars.get(&tail[1..end])
+        {
+            out.push_str(value);
+            rest = &tail[end + 1..];
+        } else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Replace control characters (including NUL and newlines) with spaces and
+/// collapse whitespace runs, so raw agent text can't garble a banner.
+pub fn sanitize(text: &str) -> String {
+    text.split(|c: char| c.is_control() || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn strip_heading(line: &str) -> &str {
+    let hashes = line.trim_start_matches('#');
+    if hashes.len() < line.len() && hashes.starts_with(' ') {
+        hashes.trim_start()
+    } else {
+        line
+    }
+}
+
+fn strip_inline(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(line.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '`' => i += 1,
+            '[' => {
+                // [text](url) -> text
+                if let Some((text, end)) = parse_link(&chars, i) {
+                    out.push_str(&strip_inline(&text));
+                    i = end;
+                } else {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+            '*' | '_' | '~' => {
+                let run = chars[i..].iter().take_while(|&&m| m == c).count();
+                let prev = i.checked_sub(1).map(|p| chars[p]);
+                let next = chars.get(i + run).copied();
+                if is_emphasis_marker(prev, next) {
+                    i += run;
+                } else {
+                    out.extend(&chars[i..i + run]);
+                    i += run;
+                }
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// A marker run is emphasis when it opens or closes a word, not when it sits
+/// inside one or stands alone between spaces.
+fn is_emphasis_marker(prev: Option<char>, next: Option<char>) -> bool {
+    let is_boundary =
+        |c: Option<char>| c.is_none_or(|c| c.is_whitespace() || c.is_ascii_punctuation());
+    let is_space = |c: Option<char>| c.is_none_or(char::is_whitespace);
+
+    match (is_boundary(prev), is_boundary(next)) {
+        (true, true) => !(is_space(prev) && is_space(next)),
+        (true, false) | (false, true) => true,
+        (false, false) => false,
+    }
+}
+
+fn parse_link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let close = start + chars[start..].iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let paren = close + 1 + chars[close + 1..].iter().position(|&c| c == ')')?;
+    Some((chars[start + 1..close].iter().collect(), paren + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> HashMap<&'static str, String> {
+        HashMap::from([
+            ("agent", "Claude".to_string()),
+            ("project", "myapp".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_render_template_substitutes_vars() {
+        assert_eq!(
+            render_template("{agent} finished in {project}", &vars()),
+            "Claude finished in myapp"
+        );
+        assert_eq!(render_template("{project}{project}", &vars()), "myappmyapp");
+    }
+
+    #[test]
+    fn test_render_template_leaves_unknown_placeholders() {
+        assert_eq!(
+            render_template("{project}: {unknown} {", &vars()),
+            "myapp: {unknown} {"
+        );
+        assert_eq!(render_template("{a{project}}", &vars()), "{amyapp}");
+    }
+
+    #[test]
+    fn test_render_template_escaped_braces() {
+        assert_eq!(
+            render_template("{{project}} is {project}", &vars()),
+            "{project} is myapp"
+        );
+        assert_eq!(render_template("}}{{", &vars()), "}{");
+    }
+
+    #[test]
+    fn test_render_template_does_not_expand_values() {
+        let vars = HashMap::from([("prompt", "use {project}".to_string())]);
+
+        assert_eq!(render_template("{prompt}", &vars), "use {project}");
+    }
+
+    #[test]
+    fn test_strip_markdown_emphasis() {
+        assert_eq!(strip_markdown("**bold** and *italic*"), "bold and italic");
+        assert_eq!(strip_markdown("__bold__ and _italic_"), "bold and italic");
+        assert_eq!(strip_markdown("~~gone~~"), "gone");
+    }
+
+    #[test]
+    fn test_strip_markdown_inline_code() {
+        assert_eq!(strip_markdown("Run `cargo test` now"), "Run cargo test now");
+    }
+
+    #[test]
+    fn test_strip_markdown_links_and_headings() {
+        assert_eq!(
+            strip_markdown("## See [the docs](https://example.com)"),
+            "See the docs"
+        );
+    }
+
+    #[test]
+    fn test_strip_markdown_leaves_identifiers_and_operators() {
+        assert_eq!(strip_markdown("fix snake_case_name"), "fix snake_case_name");
+        assert_eq!(strip_markdown("2 * 3"), "2 * 3");
+        assert_eq!(strip_markdown("a [b] c"), "a [b] c");
+        assert_eq!(strip_markdown("#123 fixed"), "#123 fixed");
+    }
+
+    #[test]
+    fn test_truncate_short_text_unchanged() {
+        assert_eq!(truncate("Fix the bug", 60), "Fix the bug");
+        assert_eq!(truncate(&"a".repeat(60), 60), "a".repeat(60));
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(
+            truncate(&"a".repeat(61), 60),
+            format!("{}...", "a".repeat(57))
+        );
+    }
+
+    #[test]
+    fn test_truncate_tiny_limits_stay_within_limit() {
+        assert_eq!(truncate("Fix the bug", 0), "");
+        assert_eq!(truncate("Fix the bug", 1), "F");
+        assert_eq!(truncate("Fix the bug", 2), "Fi");
+        assert_eq!(truncate("Fix the bug", 3), "Fix");
+        assert_eq!(truncate("Fix the bug", 4), "F...");
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        let truncated = truncate(&"日本語".repeat(30), 60);
+        assert_eq!(truncated.chars().count(), 60);
+        assert!(truncated.starts_with("日本語日本語"));
+        assert!(truncated.ends_with("..."));
+    }
+
+    #[test]
+    fn test_first_sentence() {
+        let text =
+            "Refactor the parser to use a state machine. Then add tests for every edge case.";
+        assert_eq!(
+            first_sentence(text, 100),
+            Some("Refactor the parser to use a state machine.")
+        );
+    }
+
+    #[test]
+    fn test_first_sentence_too_long() {
+        let text = "Refactor the parser to use a state machine. Then add tests.";
+        assert_eq!(first_sentence(text, 20), None);
+    }
+
+    #[test]
+    fn test_first_sentence_no_boundary() {
+        assert_eq!(first_sentence("no punctuation here at all", 100), None);
+        assert_eq!(first_sentence("see v1.2.3 for details", 100), None);
+    }
+
+    #[test]
+    fn test_first_sentence_skips_short_fragments() {
+        assert_eq!(first_sentence("Ok. Now do the rest", 100), None);
+    }
+
+    #[test]
+    fn test_strip_markdown_plain_text_unchanged() {
+        assert_eq!(strip_markdown("Fix the bug"), "Fix the bug");
+    }
+
+    #[test]
+    fn test_sanitize_control_characters() {
+        assert_eq!(
+            sanitize("Fix\0the\x1b[31m bug\r\n\tnow\x07"),
+            "Fix the [31m bug now"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_collapses_whitespace() {
+        assert_eq!(sanitize("  Fix   the\n\nbug  "), "Fix the bug");
+        assert_eq!(sanitize("\0\x01\n"), "");
+    }
+}
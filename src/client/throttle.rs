@@ -0,0 +1,298 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Identical notifications within this many milliseconds are dropped by default
+pub const DEFAULT_DEDUP_WINDOW_MS: u64 = 5_000;
+
+/// At most this many notifications are shown per minute by default
+pub const DEFAULT_MAX_PER_MINUTE: u32 = 30;
+
+const RATE_WINDOW_MS: u128 = 60_000;
+
+/// Why a notification should or shouldn't be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Show,
+    /// Same title and body as one shown within the dedup window
+    Duplicate,
+    /// The per-minute cap has been reached
+    RateLimited,
+}
+
+/// Dedup and rate limits; zero disables a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub dedup_window_ms: u64,
+    pub max_per_minute: u32,
+}
+
+impl Limits {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            dedup_window_ms: config.dedup_window_ms.unwrap_or(DEFAULT_DEDUP_WINDOW_MS),
+            max_per_minute: config.max_per_minute.unwrap_or(DEFAULT_MAX_PER_MINUTE),
+        }
+    }
+}
+
+/// Notifications shown in the last minute, persisted between `ahoy send` runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    /// (time shown in ms, content key) pairs, oldest first
+    shown: Vec<(u128, u64)>,
+    /// Running count of notifications dropped as duplicates or over the cap
+    suppressed: u64,
+}
+
+impl History {
+    /// Read the history, starting fresh if it's missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the history through a temp file renamed into place, so hooks
+    /// running at the same time never read a half-written file.
+    ///
+    /// There is no lock around load, check and save, so when two hooks race
+    /// the last save wins and the other's entry is lost. That can let an extra
+    /// notification through, but never blocks one that should be shown.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = temp_path(path);
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        if let Err(e) = fs::rename(&tmp, path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Decide whether a notification with `key` may be shown at `now_ms`
+    pub fn check(&self, key: u64, now_ms: u128, limits: &Limits) -> Verdict {
+        let dedup_window = u128::from(limits.dedup_window_ms);
+        let duplicate = self.recent(now_ms, dedup_window).any(|(_, k)| k == key);
+        if duplicate {
+            return Verdict::Duplicate;
+        }
+
+        let recent = self.recent(now_ms, RATE_WINDOW_MS).count();
+        if limits.max_per_minute > 0 && recent >= limits.max_per_minute as usize {
+            return Verdict::RateLimited;
+        }
+
+        Verdict::Show
+    }
+
+    /// Entries shown within `window_ms` before `now_ms`. Entries from the
+    /// future (the clock was set back) are ignored rather than counted as recent.
+    fn recent(&self, now_ms: u128, window_ms: u128) -> impl Iterator<Item = (u128, u64)> + '_ {
+        self.shown
+            .iter()
+            .copied()
+            .filter(move |&(at, _)| at <= now_ms && now_ms - at < window_ms)
+    }
+
+    /// Record a shown notification, forgetting ones older than a minute or
+    /// from the future
+    pub fn record_shown(&mut self, key: u64, now_ms: u128) {
+        self.shown
+            .retain(|&(at, _)| at <= now_ms && now_ms - at < RATE_WINDOW_MS);
+        self.shown.push((now_ms, key));
+    }
+
+    /// Count a suppressed notification, returning the new total
+    pub fn record_suppressed(&mut self) -> u64 {
+        self.suppressed += 1;
+        self.suppressed
+    }
+}
+
+/// Per-process temp file next to `path`, so concurrent saves don't share one
+fn temp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    PathBuf::from(tmp)
+}
+
+/// Key identifying a notification's content for dedup. Keys are stored in the
+/// state file and compared on later runs, so this is FNV-1a rather than std's
+/// hasher, whose output can change between Rust releases.
+pub fn content_key(title: &str, body: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // The title's length keeps ("ab", "c") and ("a", "bc") apart
+    let title_len = (title.len() as u64).to_le_bytes();
+    [&title_len[..], title.as_bytes(), body.as_bytes()]
+        .into_iter()
+        .flatten()
+        .fold(OFFSET_BASIS, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LIMITS: Limits = Limits {
+        dedup_window_ms: 5_000,
+        max_per_minute: 30,
+    };
+
+    /// Feed notifications through check/record like `send::run` does
+    fn feed(history: &mut History, key: u64, now_ms: u128, limits: &Limits) -> Verdict {
+        let verdict = history.check(key, now_ms, limits);
+        if verdict == Verdict::Show {
+            history.record_shown(key, now_ms);
+        } else {
+            history.record_suppressed();
+        }
+        verdict
+    }
+
+    #[test]
+    fn test_burst_of_duplicates_only_first_passes() {
+        let mut history = History::default();
+        let key = content_key("Claude Code", "[app] Task finished");
+
+        let verdicts: Vec<Verdict> = (0..10)
+            .map(|i| feed(&mut history, key, 1_000 + i * 100, &LIMITS))
+            .collect();
+
+        assert_eq!(verdicts[0], Verdict::Show);
+        assert!(verdicts[1..].iter().all(|v| *v == Verdict::Duplicate));
+        assert_eq!(history.suppressed, 9);
+    }
+
+    #[test]
+    fn test_duplicate_allowed_after_window() {
+        let mut history = History::default();
+        let key = content_key("Title", "Body");
+
+        assert_eq!(feed(&mut history, key, 1_000, &LIMITS), Verdict::Show);
+        assert_eq!(feed(&mut history, key, 6_000, &LIMITS), Verdict::Show);
+    }
+
+    #[test]
+    fn test_different_content_not_deduplicated() {
+        let mut history = History::default();
+
+        assert_eq!(
+            feed(&mut history, content_key("T", "one"), 1_000, &LIMITS),
+            Verdict::Show
+        );
+        assert_eq!(
+            feed(&mut history, content_key("T", "two"), 1_001, &LIMITS),
+            Verdict::Show
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_caps_per_minute() {
+        let mut history = History::default();
+        let limits = Limits {
+            dedup_window_ms: 0,
+            max_per_minute: 3,
+        };
+
+        let shown = (0..5)
+            .filter(|&i| feed(&mut history, i, 1_000 + i as u128, &limits) == Verdict::Show)
+            .count();
+        assert_eq!(shown, 3);
+        assert_eq!(history.check(99, 1_010, &limits), Verdict::RateLimited);
+
+        // A minute after the first ones, there's room again
+        assert_eq!(history.check(99, 61_001, &limits), Verdict::Show);
+    }
+
+    #[test]
+    fn test_zero_limits_disable_checks() {
+        let mut history = History::default();
+        let limits = Limits {
+            dedup_window_ms: 0,
+            max_per_minute: 0,
+        };
+
+        for i in 0..100 {
+            assert_eq!(feed(&mut history, 1, 1_000 + i, &limits), Verdict::Show);
+        }
+    }
+
+    #[test]
+    fn test_future_entries_are_ignored_and_evicted() {
+        let mut history = History::default();
+        let limits = Limits {
+            dedup_window_ms: 5_000,
+            max_per_minute: 1,
+        };
+        let key = content_key("Title", "Body");
+        // Shown "later" by a clock that has since been set back an hour
+        history.record_shown(key, 3_601_000);
+
+        assert_eq!(history.check(key, 1_000, &limits), Verdict::Show);
+
+        history.record_shown(key, 1_000);
+        assert_eq!(history.shown, [(1_000, key)]);
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state").join("history.json");
+        let key = content_key("Title", "Body");
+
+        let mut history = History::load(&path);
+        history.record_shown(key, 1_000);
+        history.record_suppressed();
+        history.save(&path).unwrap();
+
+        let loaded = History::load(&path);
+        assert_eq!(loaded.check(key, 1_500, &LIMITS), Verdict::Duplicate);
+        assert_eq!(loaded.suppressed, 1);
+    }
+
+    #[test]
+    fn test_content_key_is_stable() {
+        // Pinned: keys saved by one build must match keys computed by the next
+        assert_eq!(
+            content_key("Claude Code", "[app] Task finished"),
+            0x7f25_534a_655e_2bc1
+        );
+        assert_ne!(content_key("ab", "c"), content_key("a", "bc"));
+    }
+
+    #[test]
+    fn test_save_replaces_file_without_leaving_temp_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.json");
+        fs::write(&path, "not json").unwrap();
+
+        let mut history = History::default();
+        history.record_shown(1, 1_000);
+        history.save(&path).unwrap();
+
+        assert_eq!(History::load(&path).shown, [(1_000, 1)]);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_history_corrupt_file_starts_fresh() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.json");
+        fs::write(&path, "not json").unwrap();
+
+        let history = History::load(&path);
+        assert!(history.shown.is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How long `path` has gone without being modified, as of `now`
+pub fn idle_time(path: &Path, now: SystemTime) -> Result<Duration> {
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .with_context(|| format!("Failed to read modification time of {}", path.display()))?;
+    // A modification time in the future counts as activity just now
+    Ok(now.duration_since(modified).unwrap_or_default())
+}
+
+/// Block until `path` has gone `threshold` without modification.
+///
+/// Any write to the file restarts the wait. Returns how long it had been idle.
+pub fn wait_until_idle(path: &Path, threshold: Duration, poll: Duration) -> Result<Duration> {
+    loop {
+        let idle = idle_time(path, SystemTime::now())?;
+        if idle >= threshold {
+            return Ok(idle);
+        }
+        thread::sleep(poll.min(threshold - idle));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_idle_time_since_modification() {
+        let file = NamedTempFile::new().unwrap();
+        let modified = fs::metadata(file.path()).unwrap().modified().unwrap();
+
+        let idle = idle_time(file.path(), modified + Duration::from_secs(90)).unwrap();
+
+        assert_eq!(idle, Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_idle_time_future_modification_is_zero() {
+        let file = NamedTempFile::new().unwrap();
+        let modified = fs::metadata(file.path()).unwrap().modified().unwrap();
+
+        let idle = idle_time(file.path(), modified - Duration::from_secs(5)).unwrap();
+
+        assert_eq!(idle, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_idle_time_missing_file() {
+        assert!(
+            idle_time(
+                Path::new("/nonexistent/transcript.jsonl"),
+                SystemTime::now()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_wait_until_idle_returns_after_threshold() {
+        let file = NamedTempFile::new().unwrap();
+        let start = Instant::now();
+
+        let idle = wait_until_idle(
+            file.path(),
+            Duration::from_millis(50),
+            Duration::from_millis(10),
+        )
+        .unwrap();
+
+        assert!(idle >= Duration::from_millis(50));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
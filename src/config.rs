@@ -1,13 +1,469 @@
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::client::quiet_hours::QuietHours;
+use crate::client::rules::Rule;
+use crate::notify::LocalBackend;
+use crate::notify::remote::{Forward, Pushover};
+
+/// User settings from config.toml (see [`config_path`]). Every field is optional, so a
+/// missing file or key keeps the built-in behavior.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Title used when neither --title nor --agent supplies one
+    pub default_title: Option<String>,
+    /// Icon used when the notification doesn't set one
+    pub default_icon: Option<String>,
+    /// Log level filter (e.g. "warn", "debug"); RUST_LOG still takes precedence
+    pub log_level: Option<String>,
+    /// Drop notifications identical to one shown this many ms ago (0 disables)
+    pub dedup_window_ms: Option<u64>,
+    /// Cap on notifications shown per minute (0 disables)
+    pub max_per_minute: Option<u32>,
+    /// Rotate ~/.ahoy/history.jsonl to history.jsonl.1 past this many bytes (0 disables)
+    pub history_max_bytes: Option<u64>,
+    /// Body template for notifications built from agent hook data
+    pub hook_template: Option<String>,
+    /// Truncate prompts and replies from hook data to this many characters
+    pub max_body_chars: Option<usize>,
+    /// Truncate tool commands and file paths from hook data to this many characters
+    pub max_tool_chars: Option<usize>,
+    /// Reject notification JSON whose metadata has more keys than this
+    pub max_metadata_keys: Option<usize>,
+    /// Reject notification JSON whose metadata serializes to more bytes than this
+    pub max_metadata_bytes: Option<usize>,
+    /// Daily window during which notifications are dropped
+    pub quiet_hours: Option<QuietHours>,
+    /// How to show notifications on this machine: "native", "log" or "none"
+    pub local_backend: LocalBackend,
+    /// Also POST notifications to a webhook or ntfy topic
+    pub forward: Option<Forward>,
+    /// Also send notifications through Pushover
+    pub pushover: Option<Pushover>,
+    /// `[[rules]]` matched against notification metadata; the first match applies
+    pub rules: Vec<Rule>,
+    /// Top-level keys ahoy doesn't know, e.g. typos or ones from a newer version.
+    /// They are ignored with a warning rather than failing every command.
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Names of the unknown top-level keys, for warning about them
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        self.unknown.keys().map(String::as_str).collect()
+    }
+}
 
 /// Get the ahoy home directory (~/.ahoy)
 pub fn home_dir() -> PathBuf {
-    dirs::home_dir()
-        .expect("Could not determine home directory")
-        .join(".ahoy")
+    user_home().join(".ahoy")
+}
+
+fn user_home() -> PathBuf {
+    dirs::home_dir().expect("Could not determine home directory")
+}
+
+/// Directories for each kind of file ahoy keeps
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct XdgDirs {
+    /// config.toml
+    config: PathBuf,
+    /// The history of delivered notifications
+    data: PathBuf,
+    /// Cooldown and throttling state
+    state: PathBuf,
+}
+
+/// Resolve the XDG base directories for ahoy, given a lookup for environment
+/// variables. Unset, empty or relative values fall back to the spec's defaults.
+fn xdg_dirs(home: &Path, var: impl Fn(&str) -> Option<OsString>) -> XdgDirs {
+    let base = |name: &str, default: &str| {
+        var(name)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .unwrap_or_else(|| home.join(default))
+            .join("ahoy")
+    };
+    XdgDirs {
+        config: base("XDG_CONFIG_HOME", ".config"),
+        data: base("XDG_DATA_HOME", ".local/share"),
+        state: base("XDG_STATE_HOME", ".local/state"),
+    }
+}
+
+/// XDG directories on Linux; everything stays in ~/.ahoy elsewhere
+fn dirs_for_platform() -> XdgDirs {
+    if cfg!(target_os = "linux") {
+        xdg_dirs(&user_home(), |name| std::env::var_os(name))
+    } else {
+        XdgDirs {
+            config: home_dir(),
+            data: home_dir(),
+            state: home_dir(),
+        }
+    }
+}
+
+/// Directories under `home` that hold nothing but ahoy's own files: ~/.ahoy
+/// and, on Linux, ahoy's XDG config, data and state directories
+pub fn owned_dirs(home: &Path, var: impl Fn(&str) -> Option<OsString>) -> Vec<PathBuf> {
+    let mut owned = vec![home.join(".ahoy")];
+    if cfg!(target_os = "linux") {
+        let xdg = xdg_dirs(home, var);
+        for dir in [xdg.config, xdg.data, xdg.state] {
+            if !owned.contains(&dir) {
+                owned.push(dir);
+            }
+        }
+    }
+    owned
 }
 
 /// Get the bin directory (~/.ahoy/bin)
 pub fn bin_dir() -> PathBuf {
     home_dir().join("bin")
 }
+
+/// Get the macOS notification helper inside the app bundle (~/.ahoy/Ahoy.app/Contents/MacOS/ahoy-notify)
+pub fn notify_helper_path() -> PathBuf {
+    home_dir().join("Ahoy.app/Contents/MacOS/ahoy-notify")
+}
+
+/// Get the directory holding bundled notification icons (~/.ahoy/icons)
+pub fn icons_dir() -> PathBuf {
+    home_dir().join("icons")
+}
+
+/// Get the file recording when the last notification was shown
+/// (~/.ahoy/last_notification, or under $XDG_STATE_HOME/ahoy on Linux)
+pub fn last_notification_path() -> PathBuf {
+    dirs_for_platform().state.join("last_notification")
+}
+
+/// Get the file tracking recently shown notifications
+/// (~/.ahoy/recent_notifications.json, or under $XDG_STATE_HOME/ahoy on Linux)
+pub fn recent_notifications_path() -> PathBuf {
+    dirs_for_platform().state.join("recent_notifications.json")
+}
+
+/// Get the log of delivered notifications
+/// (~/.ahoy/history.jsonl, or under $XDG_DATA_HOME/ahoy on Linux)
+pub fn history_path() -> PathBuf {
+    dirs_for_platform().data.join("history.jsonl")
+}
+
+/// Get the config file path (~/.ahoy/config.toml, or under $XDG_CONFIG_HOME/ahoy on Linux)
+pub fn config_path() -> PathBuf {
+    dirs_for_platform().config.join("config.toml")
+}
+
+/// Files that used to live directly in ~/.ahoy, and the directory each now belongs in
+fn legacy_files(dirs: &XdgDirs) -> [(&'static str, &Path); 5] {
+    [
+        ("config.toml", &dirs.config),
+        ("history.jsonl", &dirs.data),
+        ("history.jsonl.1", &dirs.data),
+        ("last_notification", &dirs.state),
+        ("recent_notifications.json", &dirs.state),
+    ]
+}
+
+/// Move legacy files out of `legacy` into their new directories, skipping any
+/// that are missing or already exist at the destination. Returns the moved paths.
+fn migrate_files(legacy: &Path, dirs: &XdgDirs) -> Result<Vec<PathBuf>> {
+    let mut moved = Vec::new();
+    for (name, dir) in legacy_files(dirs) {
+        let from = legacy.join(name);
+        let to = dir.join(name);
+        if from == to || !from.is_file() || to.exists() {
+            continue;
+        }
+
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        // rename can't cross filesystems, so fall back to copying
+        if fs::rename(&from, &to).is_err() {
+            fs::copy(&from, &to)
+                .and_then(|_| fs::remove_file(&from))
+                .with_context(|| {
+                    format!("Failed to move {} to {}", from.display(), to.display())
+                })?;
+        }
+        moved.push(to);
+    }
+    Ok(moved)
+}
+
+/// Move config, history and state files from ~/.ahoy to their XDG locations
+/// on Linux. Does nothing on other platforms, where ~/.ahoy is still used.
+pub fn migrate() -> Result<Vec<PathBuf>> {
+    if !cfg!(target_os = "linux") {
+        return Ok(Vec::new());
+    }
+    migrate_files(&home_dir(), &dirs_for_platform())
+}
+
+/// Load the config file at [`config_path`], falling back to defaults if it doesn't exist
+pub fn load() -> Result<Config> {
+    load_from(&config_path())
+}
+
+/// Load a config file, falling back to defaults if it doesn't exist
+pub fn load_from(path: &Path) -> Result<Config> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::message::Urgency;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_missing_file_uses_defaults() {
+        let dir = TempDir::new().unwrap();
+
+        let config = load_from(&dir.path().join("config.toml")).unwrap();
+
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_load_overrides() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "default_title = \"Build bot\"\ndefault_icon = \"robot\"\nlog_level = \"warn\"\n",
+        )
+        .unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        assert_eq!(config.default_title.as_deref(), Some("Build bot"));
+        assert_eq!(config.default_icon.as_deref(), Some("robot"));
+        assert_eq!(config.log_level.as_deref(), Some("warn"));
+    }
+
+    #[test]
+    fn test_load_partial_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "default_title = \"Build bot\"\n").unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        assert_eq!(config.default_title.as_deref(), Some("Build bot"));
+        assert_eq!(config.default_icon, None);
+    }
+
+    #[test]
+    fn test_load_throttle_limits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "dedup_window_ms = 10000\nmax_per_minute = 0\n").unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        assert_eq!(config.dedup_window_ms, Some(10_000));
+        assert_eq!(config.max_per_minute, Some(0));
+    }
+
+    #[test]
+    fn test_load_quiet_hours_section() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "default_title = \"Ahoy\"\n\n[quiet_hours]\nstart = \"22:00\"\nend = \"08:00\"\n",
+        )
+        .unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        let quiet = config.quiet_hours.unwrap();
+        assert!(quiet.days.is_empty());
+        assert!(!quiet.allow_critical);
+    }
+
+    #[test]
+    fn test_load_forward_section() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[forward]\nurl = \"https://example.com/hook\"\n").unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        let forward = config.forward.unwrap();
+        assert_eq!(forward.url, "https://example.com/hook");
+        assert!(forward.token.is_none());
+    }
+
+    #[test]
+    fn test_load_rules() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[[rules]]\nmatch = { agent = \"codex\" }\nsuppress = true\n\n\
+             [[rules]]\nmatch = { severity = \"error\" }\nurgency = \"critical\"\n",
+        )
+        .unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        assert_eq!(config.rules.len(), 2);
+        assert!(config.rules[0].suppress);
+        assert_eq!(config.rules[1].urgency, Some(Urgency::Critical));
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: Vec<(String, OsString)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), OsString::from(v)))
+            .collect();
+        move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn test_xdg_dirs_defaults() {
+        let dirs = xdg_dirs(Path::new("/home/me"), env(&[]));
+
+        assert_eq!(dirs.config, Path::new("/home/me/.config/ahoy"));
+        assert_eq!(dirs.data, Path::new("/home/me/.local/share/ahoy"));
+        assert_eq!(dirs.state, Path::new("/home/me/.local/state/ahoy"));
+    }
+
+    #[test]
+    fn test_xdg_dirs_from_env() {
+        let dirs = xdg_dirs(
+            Path::new("/home/me"),
+            env(&[
+                ("XDG_CONFIG_HOME", "/cfg"),
+                ("XDG_DATA_HOME", "/data"),
+                ("XDG_STATE_HOME", "/run/state"),
+            ]),
+        );
+
+        assert_eq!(dirs.config, Path::new("/cfg/ahoy"));
+        assert_eq!(dirs.data, Path::new("/data/ahoy"));
+        assert_eq!(dirs.state, Path::new("/run/state/ahoy"));
+    }
+
+    #[test]
+    fn test_xdg_dirs_ignore_relative_and_empty_values() {
+        let dirs = xdg_dirs(
+            Path::new("/home/me"),
+            env(&[("XDG_CONFIG_HOME", "relative/cfg"), ("XDG_DATA_HOME", "")]),
+        );
+
+        assert_eq!(dirs.config, Path::new("/home/me/.config/ahoy"));
+        assert_eq!(dirs.data, Path::new("/home/me/.local/share/ahoy"));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_owned_dirs_include_xdg_dirs_once() {
+        let owned = owned_dirs(
+            Path::new("/home/me"),
+            env(&[("XDG_CONFIG_HOME", "/shared"), ("XDG_DATA_HOME", "/shared")]),
+        );
+
+        assert_eq!(
+            owned,
+            [
+                Path::new("/home/me/.ahoy"),
+                Path::new("/shared/ahoy"),
+                Path::new("/home/me/.local/state/ahoy"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_files_moves_legacy_files() {
+        let home = TempDir::new().unwrap();
+        let legacy = home.path().join(".ahoy");
+        fs::create_dir_all(legacy.join("bin")).unwrap();
+        fs::write(legacy.join("config.toml"), "default_title = \"Old\"\n").unwrap();
+        fs::write(legacy.join("history.jsonl"), "{}\n").unwrap();
+        fs::write(legacy.join("last_notification"), "1").unwrap();
+        let dirs = xdg_dirs(home.path(), env(&[]));
+
+        let moved = migrate_files(&legacy, &dirs).unwrap();
+
+        assert_eq!(moved.len(), 3);
+        assert_eq!(
+            fs::read_to_string(dirs.config.join("config.toml")).unwrap(),
+            "default_title = \"Old\"\n"
+        );
+        assert!(dirs.data.join("history.jsonl").is_file());
+        assert!(dirs.state.join("last_notification").is_file());
+        assert!(!legacy.join("config.toml").exists());
+        // Installed binaries stay where the hooks expect them
+        assert!(legacy.join("bin").is_dir());
+
+        // A second run has nothing left to move
+        assert!(migrate_files(&legacy, &dirs).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_files_keeps_existing_destination() {
+        let home = TempDir::new().unwrap();
+        let legacy = home.path().join(".ahoy");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(legacy.join("config.toml"), "old").unwrap();
+        let dirs = xdg_dirs(home.path(), env(&[]));
+        fs::create_dir_all(&dirs.config).unwrap();
+        fs::write(dirs.config.join("config.toml"), "new").unwrap();
+
+        assert!(migrate_files(&legacy, &dirs).unwrap().is_empty());
+        assert_eq!(
+            fs::read_to_string(dirs.config.join("config.toml")).unwrap(),
+            "new"
+        );
+        assert!(legacy.join("config.toml").exists());
+    }
+
+    #[test]
+    fn test_load_malformed_file_errors() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "default_title = \n").unwrap();
+
+        let err = load_from(&path).unwrap_err();
+
+        assert!(format!("{:#}", err).contains("Failed to parse"));
+        assert!(format!("{:#}", err).contains("config.toml"));
+    }
+
+    #[test]
+    fn test_load_unknown_keys_are_kept_for_warning() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "default_tilte = \"typo\"\nmax_per_minute = 5\n[old_section]\nkey = 1\n",
+        )
+        .unwrap();
+
+        let config = load_from(&path).unwrap();
+
+        assert_eq!(config.unknown_keys(), ["default_tilte", "old_section"]);
+        assert_eq!(config.max_per_minute, Some(5));
+        assert_eq!(config.default_title, None);
+    }
+}
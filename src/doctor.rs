@@ -0,0 +1,410 @@
+use anyhow::Result;
+use std::ffi::OsStr;
+use std::path::Path;
+
+use crate::config::{self, Config};
+use crate::install::{aider, claude, codex, cursor, gemini};
+use crate::notify::LocalBackend;
+
+/// Bundle identifier the helper presents to Notification Center
+#[cfg(target_os = "macos")]
+const HELPER_BUNDLE_ID: &str = "rs.ahoy.notify.fresh";
+
+/// Outcome of a single check, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Pass,
+    /// Something looks off, but notifications can still work
+    Warn,
+    /// Notifications won't work until this is fixed
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+pub fn check_home_dir(home: &Path) -> CheckResult {
+    if home.is_dir() {
+        CheckResult::pass("Home directory", home.display().to_string())
+    } else {
+        CheckResult::fail(
+            "Home directory",
+            format!("{} does not exist", home.display()),
+            "Run the install script to set up ~/.ahoy",
+        )
+    }
+}
+
+pub fn check_config(path: &Path) -> CheckResult {
+    match config::load_from(path) {
+        Ok(_) if path.exists() => CheckResult::pass("Config", path.display().to_string()),
+        Ok(_) => CheckResult::pass("Config", "no config file, using defaults"),
+        Err(e) => CheckResult::fail(
+            "Config",
+            format!("{:#}", e),
+            format!("Fix or remove {}", path.display()),
+        ),
+    }
+}
+
+/// Hooks call ahoy by absolute path, so this only matters for running it by hand
+pub fn check_bin_on_path(bin_dir: &Path, path_var: Option<&OsStr>) -> CheckResult {
+    let on_path = path_var.is_some_and(|paths| std::env::split_paths(paths).any(|p| p == bin_dir));
+    if on_path {
+        CheckResult::pass("PATH", format!("{} is on PATH", bin_dir.display()))
+    } else {
+        CheckResult::warn(
+            "PATH",
+            format!("{} is not on PATH", bin_dir.display()),
+            format!(
+                "Add export PATH=\"{}:$PATH\" to your shell config",
+                bin_dir.display()
+            ),
+        )
+    }
+}
+
+/// Whether notifications have somewhere to go: this platform's notifier, or
+/// a remote backend when there isn't one. `macos` is whether the native
+/// notifier is the macOS helper, the only one implemented so far.
+pub fn check_notifier(helper: &Path, config: &Config, macos: bool) -> CheckResult {
+    let remote = config.forward.is_some() || config.pushover.is_some();
+    match config.local_backend {
+        LocalBackend::Log => {
+            return CheckResult::pass("Notification helper", "not used, local_backend = \"log\"");
+        }
+        LocalBackend::None => {
+            return CheckResult::pass("Notification helper", "not used, local_backend = \"none\"");
+        }
+        LocalBackend::Native => {}
+    }
+
+    if macos {
+        if helper.is_file() {
+            CheckResult::pass("Notification helper", helper.display().to_string())
+        } else {
+            CheckResult::fail(
+                "Notification helper",
+                format!("{} is missing", helper.display()),
+                "Rerun the install script to build Ahoy.app",
+            )
+        }
+    } else if remote {
+        CheckResult::pass(
+            "Notification helper",
+            "no native notifier on this platform, using the remote backend",
+        )
+    } else {
+        CheckResult::warn(
+            "Notification helper",
+            "native notifications are only implemented on macOS so far",
+            "Use the [forward] or [pushover] config sections to get notifications elsewhere",
+        )
+    }
+}
+
+/// Whether Notification Center knows about the helper, given the output of
+/// `defaults read com.apple.ncprefs apps`. macOS only registers an app once it
+/// has posted a notification, and doesn't expose whether alerts are allowed.
+pub fn check_notification_registration(ncprefs: Option<&str>, bundle_id: &str) -> CheckResult {
+    match ncprefs {
+        Some(apps) if apps.contains(bundle_id) => CheckResult::pass(
+            "Notification permission",
+            "Ahoy is registered with Notification Center",
+        ),
+        Some(_) => CheckResult::warn(
+            "Notification permission",
+            "Ahoy has not been registered with Notification Center yet",
+            "Run `ahoy send test`, then allow Ahoy in System Settings > Notifications",
+        ),
+        None => CheckResult::warn(
+            "Notification permission",
+            "could not read Notification Center settings",
+            "Check that Ahoy is allowed in System Settings > Notifications",
+        ),
+    }
+}
+
+pub fn check_hooks(agents: &[(&str, bool)]) -> CheckResult {
+    let installed: Vec<&str> = agents
+        .iter()
+        .filter(|(_, installed)| *installed)
+        .map(|(name, _)| *name)
+        .collect();
+
+    if installed.is_empty() {
+        CheckResult::warn(
+            "Agent hooks",
+            "no agent hooks are installed",
+            "Run `ahoy install` for detected agents, or `ahoy install <agent>`",
+        )
+    } else {
+        CheckResult::pass("Agent hooks", installed.join(", "))
+    }
+}
+
+/// Run every check against the real environment
+pub fn run_checks() -> Vec<CheckResult> {
+    let mut results = vec![
+        check_home_dir(&config::home_dir()),
+        check_config(&config::config_path()),
+        check_bin_on_path(&config::bin_dir(), std::env::var_os("PATH").as_deref()),
+        check_notifier(
+            &config::notify_helper_path(),
+            &config::load().unwrap_or_default(),
+            cfg!(target_os = "macos"),
+        ),
+    ];
+
+    #[cfg(target_os = "macos")]
+    {
+        let ncprefs = std::process::Command::new("defaults")
+            .args(["read", "com.apple.ncprefs", "apps"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+        results.push(check_notification_registration(
+            ncprefs.as_deref(),
+            HELPER_BUNDLE_ID,
+        ));
+    }
+
+    results.push(check_hooks(&[
+        ("Claude Code", claude::is_installed()),
+        ("Codex", codex::is_installed()),
+        ("Gemini CLI", gemini::is_installed()),
+        ("Cursor", cursor::is_installed()),
+        ("Aider", aider::is_installed()),
+    ]));
+
+    results
+}
+
+/// Worst status among the results; no checks counts as passing
+pub fn overall(results: &[CheckResult]) -> Status {
+    results
+        .iter()
+        .map(|r| r.status)
+        .max()
+        .unwrap_or(Status::Pass)
+}
+
+/// Format results one per line, with hints indented underneath
+pub fn render(results: &[CheckResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        out.push_str(&format!(
+            "[{}] {}: {}\n",
+            result.status.label(),
+            result.name,
+            result.detail
+        ));
+        if let Some(hint) = &result.hint {
+            out.push_str(&format!("       {}\n", hint));
+        }
+    }
+    out
+}
+
+/// Print a diagnosis of the setup, failing if any check failed
+pub fn run() -> Result<()> {
+    let results = run_checks();
+    print!("{}", render(&results));
+
+    let failures = results.iter().filter(|r| r.status == Status::Fail).count();
+    if overall(&results) == Status::Fail {
+        anyhow::bail!("{} check(s) failed", failures);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_overall_is_worst_status() {
+        let pass = CheckResult::pass("a", "ok");
+        let warn = CheckResult::warn("b", "hmm", "fix b");
+        let fail = CheckResult::fail("c", "broken", "fix c");
+
+        assert_eq!(overall(&[]), Status::Pass);
+        assert_eq!(overall(std::slice::from_ref(&pass)), Status::Pass);
+        assert_eq!(overall(&[pass.clone(), warn.clone()]), Status::Warn);
+        assert_eq!(overall(&[warn, fail, pass]), Status::Fail);
+    }
+
+    #[test]
+    fn test_render_includes_hints() {
+        let out = render(&[
+            CheckResult::pass("Config", "defaults"),
+            CheckResult::fail("Notification helper", "missing", "Rerun the install script"),
+        ]);
+
+        assert_eq!(
+            out,
+            "[pass] Config: defaults\n\
+             [FAIL] Notification helper: missing\n       Rerun the install script\n"
+        );
+    }
+
+    #[test]
+    fn test_check_home_dir() {
+        let dir = TempDir::new().unwrap();
+
+        assert_eq!(check_home_dir(dir.path()).status, Status::Pass);
+        assert_eq!(
+            check_home_dir(&dir.path().join("missing")).status,
+            Status::Fail
+        );
+    }
+
+    #[test]
+    fn test_check_config_malformed_fails() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        assert_eq!(check_config(&path).status, Status::Pass);
+
+        std::fs::write(&path, "default_title = \n").unwrap();
+        let result = check_config(&path);
+        assert_eq!(result.status, Status::Fail);
+        assert!(result.detail.contains("Failed to parse"));
+    }
+
+    #[test]
+    fn test_check_bin_on_path() {
+        let bin = Path::new("/home/me/.ahoy/bin");
+        let path_var = std::env::join_paths(["/usr/bin", "/home/me/.ahoy/bin"]).unwrap();
+
+        assert_eq!(check_bin_on_path(bin, Some(&path_var)).status, Status::Pass);
+        assert_eq!(
+            check_bin_on_path(bin, Some(&OsString::from("/usr/bin"))).status,
+            Status::Warn
+        );
+        assert_eq!(check_bin_on_path(bin, None).status, Status::Warn);
+    }
+
+    #[test]
+    fn test_check_notifier_macos_helper() {
+        let dir = TempDir::new().unwrap();
+        let helper = dir.path().join("ahoy-notify");
+        let config = Config::default();
+
+        assert_eq!(check_notifier(&helper, &config, true).status, Status::Fail);
+        std::fs::write(&helper, "").unwrap();
+        assert_eq!(check_notifier(&helper, &config, true).status, Status::Pass);
+    }
+
+    #[test]
+    fn test_check_notifier_without_native_notifier_warns() {
+        let result = check_notifier(Path::new("/missing"), &Config::default(), false);
+
+        assert_eq!(result.status, Status::Warn);
+    }
+
+    #[test]
+    fn test_check_notifier_remote_backend_passes() {
+        let config: Config =
+            toml::from_str("[forward]\nurl = \"https://ntfy.sh/agents\"\nkind = \"ntfy\"\n")
+                .unwrap();
+
+        assert_eq!(
+            check_notifier(Path::new("/missing"), &config, false).status,
+            Status::Pass
+        );
+    }
+
+    #[test]
+    fn test_check_notifier_local_backend_off_passes() {
+        for backend in [LocalBackend::Log, LocalBackend::None] {
+            let config = Config {
+                local_backend: backend,
+                ..Default::default()
+            };
+
+            for macos in [true, false] {
+                assert_eq!(
+                    check_notifier(Path::new("/missing"), &config, macos).status,
+                    Status::Pass
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_notification_registration() {
+        let apps = "({ \"bundle-id\" = \"rs.ahoy.notify.fresh\"; flags = 41951246; })";
+
+        assert_eq!(
+            check_notification_registration(Some(apps), "rs.ahoy.notify.fresh").status,
+            Status::Pass
+        );
+        assert_eq!(
+            check_notification_registration(Some("()"), "rs.ahoy.notify.fresh").status,
+            Status::Warn
+        );
+        assert_eq!(
+            check_notification_registration(None, "rs.ahoy.notify.fresh").status,
+            Status::Warn
+        );
+    }
+
+    #[test]
+    fn test_check_hooks() {
+        let none = check_hooks(&[("Claude Code", false), ("Codex", false)]);
+        assert_eq!(none.status, Status::Warn);
+
+        let some = check_hooks(&[("Claude Code", true), ("Codex", false), ("Aider", true)]);
+        assert_eq!(some.status, Status::Pass);
+        assert_eq!(some.detail, "Claude Code, Aider");
+    }
+}
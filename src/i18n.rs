@@ -0,0 +1,111 @@
+/// Env var selecting the notification text locale (e.g. "de", "fr_CA")
+pub const LOCALE_ENV: &str = "AHOY_LOCALE";
+
+const DEFAULT_LOCALE: &str = "en";
+
+/// Fixed phrases ahoy puts into notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phrase {
+    TaskFinished,
+    NeedsPermission,
+    WaitingForInput,
+    NoMessage,
+    AgentIdle,
+    /// Put before how long a task took, as in "Done in 2m14s"
+    DoneIn,
+}
+
+/// Look up a phrase in the configured locale
+pub fn text(phrase: Phrase) -> &'static str {
+    text_for(&locale(), phrase)
+}
+
+/// The configured locale, defaulting to English
+pub fn locale() -> String {
+    std::env::var(LOCALE_ENV)
+        .ok()
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Look up a phrase in the bundled catalog for `locale`.
+///
+/// Only the language part of the locale is used ("fr_CA.UTF-8" -> "fr").
+/// Unknown languages fall back to English.
+pub fn text_for(locale: &str, phrase: Phrase) -> &'static str {
+    let language = locale
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match (language.as_str(), phrase) {
+        ("de", Phrase::TaskFinished) => "Aufgabe abgeschlossen",
+        ("de", Phrase::NeedsPermission) => "Benötigt Berechtigung",
+        ("de", Phrase::WaitingForInput) => "Wartet auf Ihre Eingabe",
+        ("de", Phrase::NoMessage) => "(keine Nachricht)",
+        ("de", Phrase::AgentIdle) => "Keine Aktivität mehr vom Agenten",
+        ("de", Phrase::DoneIn) => "Fertig nach",
+        ("es", Phrase::TaskFinished) => "Tarea terminada",
+        ("es", Phrase::NeedsPermission) => "Necesita permiso",
+        ("es", Phrase::WaitingForInput) => "Esperando tu respuesta",
+        ("es", Phrase::NoMessage) => "(sin mensaje)",
+        ("es", Phrase::AgentIdle) => "El agente no muestra actividad",
+        ("es", Phrase::DoneIn) => "Terminado en",
+        ("fr", Phrase::TaskFinished) => "Tâche terminée",
+        ("fr", Phrase::NeedsPermission) => "Autorisation requise",
+        ("fr", Phrase::WaitingForInput) => "En attente de votre saisie",
+        ("fr", Phrase::NoMessage) => "(aucun message)",
+        ("fr", Phrase::AgentIdle) => "Aucune activité de l'agent",
+        ("fr", Phrase::DoneIn) => "Terminé en",
+        ("ja", Phrase::TaskFinished) => "タスク完了",
+        ("ja", Phrase::NeedsPermission) => "許可が必要です",
+        ("ja", Phrase::WaitingForInput) => "入力待ちです",
+        ("ja", Phrase::NoMessage) => "（メッセージなし）",
+        ("ja", Phrase::AgentIdle) => "エージェントの動きが止まっています",
+        ("ja", Phrase::DoneIn) => "所要時間",
+        (_, Phrase::TaskFinished) => "Task finished",
+        (_, Phrase::NeedsPermission) => "Needs permission",
+        (_, Phrase::WaitingForInput) => "Waiting for your input",
+        (_, Phrase::NoMessage) => "(no message)",
+        (_, Phrase::AgentIdle) => "No agent activity",
+        (_, Phrase::DoneIn) => "Done in",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_for_english() {
+        assert_eq!(text_for("en", Phrase::TaskFinished), "Task finished");
+        assert_eq!(
+            text_for("en", Phrase::WaitingForInput),
+            "Waiting for your input"
+        );
+    }
+
+    #[test]
+    fn test_text_for_translated_locale() {
+        assert_eq!(
+            text_for("de", Phrase::TaskFinished),
+            "Aufgabe abgeschlossen"
+        );
+    }
+
+    #[test]
+    fn test_text_for_uses_language_part() {
+        assert_eq!(
+            text_for("fr_CA.UTF-8", Phrase::NeedsPermission),
+            "Autorisation requise"
+        );
+        assert_eq!(text_for("ES-mx", Phrase::TaskFinished), "Tarea terminada");
+    }
+
+    #[test]
+    fn test_text_for_unknown_locale_falls_back_to_english() {
+        assert_eq!(text_for("xx", Phrase::NeedsPermission), "Needs permission");
+        assert_eq!(text_for("", Phrase::TaskFinished), "Task finished");
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::claude::shell_quote;
+use super::setup::InstallOptions;
+use crate::config;
+
+/// Marks a wrapper as ours, so install and uninstall never touch a user's own script
+const WRAPPER_MARKER: &str = "# Installed by ahoy: runs aider and notifies when it exits";
+
+/// Aider has no hook system, so ahoy installs an `aider` wrapper next to its own binary
+fn wrapper_path() -> PathBuf {
+    // Allow test override via env var
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return PathBuf::from(test_home).join(".ahoy/bin/aider");
+    }
+
+    config::bin_dir().join("aider")
+}
+
+/// Shell script that runs the real aider, then sends a notification with its exit status
+fn create_wrapper(wrapper: &Path) -> String {
+    let ahoy_bin = config::bin_dir().join("ahoy");
+    format!(
+        r#"#!/bin/sh
+{marker}
+# Find the real aider on PATH, skipping this wrapper however its directory is
+# spelled (trailing slash, symlink, ...) by comparing the files themselves
+wrapper={wrapper}
+real_aider=
+IFS=:
+for dir in $PATH; do
+    if [ -x "$dir/aider" ] && ! [ "$dir/aider" -ef "$wrapper" ]; then
+        real_aider="$dir/aider"
+        break
+    fi
+done
+unset IFS
+
+if [ -z "$real_aider" ]; then
+    echo "ahoy: could not find aider on PATH" >&2
+    exit 127
+fi
+
+"$real_aider" "$@"
+status=$?
+{ahoy} send --agent aider --urgency-from-exit "$status" 'Aider session ended' >/dev/null 2>&1
+exit "$status"
+"#,
+        marker = WRAPPER_MARKER,
+        wrapper = shell_quote(&wrapper.to_string_lossy()),
+        ahoy = shell_quote(&ahoy_bin.to_string_lossy()),
+    )
+}
+
+fn is_ahoy_wrapper(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| content.contains(WRAPPER_MARKER))
+        .unwrap_or(false)
+}
+
+pub fn install() -> Result<()> {
+    install_with(&InstallOptions::default())
+}
+
+pub fn install_with(options: &InstallOptions) -> Result<()> {
+    let wrapper = wrapper_path();
+    let wrapper_dir = wrapper
+        .parent()
+        .context("aider wrapper path has no parent")?;
+
+    if wrapper.exists() {
+        if !is_ahoy_wrapper(&wrapper) {
+            anyhow::bail!(
+                "{} already exists and was not created by ahoy; not overwriting it",
+                wrapper.display()
+            );
+        }
+        if !options.replace {
+            println!("Ahoy wrapper is already installed for Aider");
+            println!("Use --replace to upgrade the existing wrapper");
+            return Ok(());
+        }
+    }
+
+    fs::create_dir_all(wrapper_dir)?;
+    fs::write(&wrapper, create_wrapper(&wrapper)).context("Failed to write aider wrapper")?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&wrapper, fs::Permissions::from_mode(0o755))
+            .context("Failed to make aider wrapper executable")?;
+    }
+
+    println!("Installed ahoy wrapper for Aider:");
+    println!("  - notifies when an aider session exits");
+    println!();
+    println!("Wrapper: {}", wrapper.display());
+    println!(
+        "Make sure {} comes before aider's own directory on your PATH:",
+        wrapper_dir.display()
+    );
+    println!("  export PATH=\"{}:$PATH\"", wrapper_dir.display());
+
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    let wrapper = wrapper_path();
+
+    if !is_ahoy_wrapper(&wrapper) {
+        println!("Ahoy wrapper was not installed for Aider");
+        return Ok(());
+    }
+
+    fs::remove_file(&wrapper).context("Failed to remove aider wrapper")?;
+    println!("Removed ahoy wrapper for Aider: {}", wrapper.display());
+
+    Ok(())
+}
+
+pub fn is_installed() -> bool {
+    is_ahoy_wrapper(&wrapper_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_wrapper_format() {
+        let script = create_wrapper(Path::new("/home/me/.ahoy/bin/aider"));
+
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(WRAPPER_MARKER));
+        assert!(script.contains("wrapper='/home/me/.ahoy/bin/aider'"));
+        assert!(script.contains("send --agent aider --urgency-from-exit \"$status\""));
+        assert!(script.contains("exit \"$status\""));
+    }
+}
@@ -3,7 +3,10 @@ use serde_json::{Value, json};
 use std::fs;
 use std::path::PathBuf;
 
+use super::settings::{self, SettingsFormat};
+use super::setup::InstallOptions;
 use crate::config;
+use crate::i18n::{self, Phrase};
 
 const HOOK_MARKER: &str = "ahoy";
 
@@ -18,10 +21,28 @@ fn settings_path() -> PathBuf {
         .join(".claude/settings.json")
 }
 
+/// Settings path with any symlink resolved, so writes land on the real file
+fn resolve_settings_file() -> Result<PathBuf> {
+    let path = settings_path();
+    let target = settings::resolve_target(&path)?;
+    if target != path {
+        println!(
+            "Claude settings.json is a symlink, writing to {}",
+            target.display()
+        );
+    }
+    Ok(target)
+}
+
 fn ahoy_bin_path() -> String {
     config::bin_dir().join("ahoy").to_string_lossy().to_string()
 }
 
+/// Single-quote a string for safe use in a hook's shell command
+pub(super) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
 fn create_stop_hook() -> Value {
     json!({
         "matcher": "",
@@ -29,7 +50,7 @@ fn create_stop_hook() -> Value {
             {
                 "type": "command",
                 "command": format!(
-                    "{} send --from-claude -t 'Claude Code' --activate \"$__CFBundleIdentifier\"",
+                    "{} send --from-claude --agent claude --activate \"$__CFBundleIdentifier\"",
                     ahoy_bin_path()
                 ),
                 "timeout": 5000
@@ -46,8 +67,9 @@ fn create_notification_hooks() -> Vec<Value> {
                 {
                     "type": "command",
                     "command": format!(
-                        "{} send -t 'Claude Code' 'Waiting for your input' --activate \"$__CFBundleIdentifier\"",
-                        ahoy_bin_path()
+                        "{} send --agent claude {} --activate \"$__CFBundleIdentifier\"",
+                        ahoy_bin_path(),
+                        shell_quote(i18n::text(Phrase::WaitingForInput))
                     ),
                     "timeout": 5000
                 }
@@ -59,7 +81,7 @@ fn create_notification_hooks() -> Vec<Value> {
                 {
                     "type": "command",
                     "command": format!(
-                        "{} send --from-claude -t 'Claude Code' --activate \"$__CFBundleIdentifier\"",
+                        "{} send --from-claude --agent claude --activate \"$__CFBundleIdentifier\"",
                         ahoy_bin_path()
                     ),
                     "timeout": 5000
@@ -70,12 +92,20 @@ fn create_notification_hooks() -> Vec<Value> {
 }
 
 pub fn install() -> Result<()> {
-    let settings_file = settings_path();
+    install_with(&InstallOptions::default())
+}
+
+pub fn install_with(options: &InstallOptions) -> Result<()> {
+    let settings_file = resolve_settings_file()?;
+
+    let existing = if settings_file.exists() {
+        Some(fs::read_to_string(&settings_file).context("Failed to read Claude settings.json")?)
+    } else {
+        None
+    };
 
-    let mut settings: Value = if settings_file.exists() {
-        let content =
-            fs::read_to_string(&settings_file).context("Failed to read Claude settings.json")?;
-        serde_json::from_str(&content).context("Failed to parse Claude settings.json")?
+    let mut settings: Value = if let Some(content) = &existing {
+        serde_json::from_str(content).context("Failed to parse Claude settings.json")?
     } else {
         if let Some(parent) = settings_file.parent() {
             fs::create_dir_all(parent)?;
@@ -103,25 +133,22 @@ pub fn install() -> Result<()> {
         .and_then(|s| s.as_array_mut())
         .context("Stop is not a JSON array")?;
 
-    let already_installed = stop_hooks.iter().any(|hook| {
-        hook.get("hooks")
-            .and_then(|h| h.as_array())
-            .map(|arr| {
-                arr.iter().any(|h| {
-                    h.get("command")
-                        .and_then(|c| c.as_str())
-                        .map(|cmd| cmd.contains(HOOK_MARKER))
-                        .unwrap_or(false)
-                })
-            })
-            .unwrap_or(false)
-    });
+    let already_installed = stop_hooks.iter().any(contains_ahoy_marker);
 
-    if already_installed {
+    if already_installed && !options.replace {
         println!("Ahoy hook is already installed for Claude Code");
+        println!("Use --replace to upgrade the existing hook commands");
         return Ok(());
     }
 
+    // With --replace, drop existing ahoy hooks so the current commands are re-added
+    let mut replaced = 0;
+    if options.replace {
+        let original_len = stop_hooks.len();
+        stop_hooks.retain(|hook| !contains_ahoy_marker(hook));
+        replaced += original_len - stop_hooks.len();
+    }
+
     stop_hooks.push(create_stop_hook());
 
     if !hooks.contains_key("Notification") {
@@ -132,13 +159,25 @@ pub fn install() -> Result<()> {
         .and_then(|s| s.as_array_mut())
         .context("Notification is not a JSON array")?;
 
+    if options.replace {
+        let original_len = notification_hooks.len();
+        notification_hooks.retain(|hook| !contains_ahoy_marker(hook));
+        replaced += original_len - notification_hooks.len();
+    }
+
     for hook in create_notification_hooks() {
         notification_hooks.push(hook);
     }
 
-    let content = serde_json::to_string_pretty(&settings)?;
+    let content = settings::render(&settings, options.settings_format, existing.as_deref())?;
     fs::write(&settings_file, &content).context("Failed to write Claude settings.json")?;
 
+    if replaced > 0 {
+        println!(
+            "Replaced {} existing ahoy hook(s) for Claude Code",
+            replaced
+        );
+    }
     println!("Installed ahoy hooks for Claude Code:");
     println!("  - Stop: notifies when Claude finishes");
     println!("  - Notification (idle_prompt): notifies when waiting for input");
@@ -150,7 +189,7 @@ pub fn install() -> Result<()> {
 }
 
 pub fn uninstall() -> Result<()> {
-    let settings_file = settings_path();
+    let settings_file = resolve_settings_file()?;
 
     if !settings_file.exists() {
         println!("Claude settings.json not found - nothing to uninstall");
@@ -184,7 +223,7 @@ pub fn uninstall() -> Result<()> {
     }
 
     if removed_stop || removed_notification {
-        let content = serde_json::to_string_pretty(&settings)?;
+        let content = settings::render(&settings, SettingsFormat::Preserve, Some(&content))?;
         fs::write(&settings_file, &content).context("Failed to write Claude settings.json")?;
         println!("Removed ahoy hooks from Claude Code:");
         if removed_stop {
@@ -200,7 +239,7 @@ pub fn uninstall() -> Result<()> {
     Ok(())
 }
 
-fn contains_ahoy_marker(hook: &Value) -> bool {
+pub(super) fn contains_ahoy_marker(hook: &Value) -> bool {
     hook.get("hooks")
         .and_then(|h| h.as_array())
         .map(|arr| {
@@ -334,6 +373,7 @@ mod tests {
         let command = command_hook["command"].as_str().unwrap();
         assert!(command.contains("ahoy"));
         assert!(command.contains("--from-claude"));
+        assert!(command.contains("--agent claude"));
     }
 
     #[test]
@@ -357,6 +397,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(
+            shell_quote("Waiting for your input"),
+            "'Waiting for your input'"
+        );
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
     #[test]
     fn test_ahoy_bin_path_format() {
         let path = ahoy_bin_path();
@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, value};
+
+use super::settings;
+use super::setup::InstallOptions;
+use crate::config;
+
+const HOOK_MARKER: &str = "ahoy";
+
+fn config_path() -> PathBuf {
+    // Allow test override via env var
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return PathBuf::from(test_home).join(".codex/config.toml");
+    }
+
+    dirs::home_dir()
+        .expect("Could not determine home directory")
+        .join(".codex/config.toml")
+}
+
+/// Config path with any symlink resolved, so writes land on the real file
+fn resolve_config_file() -> Result<PathBuf> {
+    let path = config_path();
+    let target = settings::resolve_target(&path)?;
+    if target != path {
+        println!(
+            "Codex config.toml is a symlink, writing to {}",
+            target.display()
+        );
+    }
+    Ok(target)
+}
+
+/// Codex runs its `notify` program with the event JSON appended as the last argument
+fn create_notify_command() -> Array {
+    let mut command = Array::new();
+    command.push(config::bin_dir().join("ahoy").to_string_lossy().to_string());
+    for arg in ["send", "--agent", "codex", "--from-codex"] {
+        command.push(arg);
+    }
+    command
+}
+
+/// Codex allows a single `notify` program, so the whole command is either ours or not
+fn is_ahoy_notify(item: &Item) -> bool {
+    item.as_array()
+        .map(|arr| {
+            arr.iter()
+                .any(|arg| arg.as_str().is_some_and(|s| s.contains(HOOK_MARKER)))
+        })
+        .unwrap_or(false)
+}
+
+fn read_config(path: &Path) -> Result<DocumentMut> {
+    let content = fs::read_to_string(path).context("Failed to read Codex config.toml")?;
+    content
+        .parse::<DocumentMut>()
+        .context("Failed to parse Codex config.toml")
+}
+
+pub fn install() -> Result<()> {
+    install_with(&InstallOptions::default())
+}
+
+pub fn install_with(options: &InstallOptions) -> Result<()> {
+    let config_file = resolve_config_file()?;
+
+    let mut config = if config_file.exists() {
+        read_config(&config_file)?
+    } else {
+        if let Some(parent) = config_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        DocumentMut::new()
+    };
+
+    let mut replaced = false;
+    if let Some(existing) = config.get("notify") {
+        if !is_ahoy_notify(existing) {
+            anyhow::bail!(
+                "Codex config.toml already sets notify to another program ({}); \
+                 remove it to let ahoy handle Codex notifications",
+                existing.to_string().trim()
+            );
+        }
+        if !options.replace {
+            println!("Ahoy hook is already installed for Codex");
+            println!("Use --replace to upgrade the existing hook command");
+            return Ok(());
+        }
+        replaced = true;
+    }
+
+    // Top-level keys must precede any tables, which toml_edit handles on insert
+    config.insert("notify", value(create_notify_command()));
+
+    fs::write(&config_file, config.to_string()).context("Failed to write Codex config.toml")?;
+
+    if replaced {
+        println!("Replaced existing ahoy hook for Codex");
+    }
+    println!("Installed ahoy hook for Codex:");
+    println!("  - notify: notifies when Codex finishes a turn");
+    println!();
+    println!("Config file: {}", config_file.display());
+
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    let config_file = resolve_config_file()?;
+
+    if !config_file.exists() {
+        println!("Codex config.toml not found - nothing to uninstall");
+        return Ok(());
+    }
+
+    let mut config = read_config(&config_file)?;
+
+    if !config.get("notify").is_some_and(is_ahoy_notify) {
+        println!("Ahoy hook was not installed for Codex");
+        return Ok(());
+    }
+
+    config.remove("notify");
+    fs::write(&config_file, config.to_string()).context("Failed to write Codex config.toml")?;
+    println!("Removed ahoy hook from Codex:");
+    println!("  - notify command");
+
+    Ok(())
+}
+
+pub fn is_installed() -> bool {
+    let Ok(content) = fs::read_to_string(config_path()) else {
+        return false;
+    };
+
+    let Ok(config) = content.parse::<DocumentMut>() else {
+        return false;
+    };
+
+    config.get("notify").is_some_and(is_ahoy_notify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ahoy_notify_true() {
+        let config: DocumentMut = r#"notify = ["/path/to/ahoy", "send", "--from-codex"]"#
+            .parse()
+            .unwrap();
+
+        assert!(is_ahoy_notify(&config["notify"]));
+    }
+
+    #[test]
+    fn test_is_ahoy_notify_false() {
+        let config: DocumentMut = r#"notify = ["notify-send", "Codex"]"#.parse().unwrap();
+
+        assert!(!is_ahoy_notify(&config["notify"]));
+    }
+
+    #[test]
+    fn test_is_ahoy_notify_not_an_array() {
+        let config: DocumentMut = r#"notify = "ahoy""#.parse().unwrap();
+
+        assert!(!is_ahoy_notify(&config["notify"]));
+    }
+
+    #[test]
+    fn test_create_notify_command_format() {
+        let command = create_notify_command();
+        let args: Vec<&str> = command.iter().filter_map(|a| a.as_str()).collect();
+
+        assert!(args[0].ends_with("ahoy"));
+        assert_eq!(&args[1..], ["send", "--agent", "codex", "--from-codex"]);
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::PathBuf;
+
+use super::claude::shell_quote;
+use super::settings::{self, SettingsFormat};
+use super::setup::InstallOptions;
+use crate::config;
+use crate::i18n::{self, Phrase};
+
+const HOOK_MARKER: &str = "ahoy";
+
+fn hooks_path() -> PathBuf {
+    // Allow test override via env var
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return PathBuf::from(test_home).join(".cursor/hooks.json");
+    }
+
+    dirs::home_dir()
+        .expect("Could not determine home directory")
+        .join(".cursor/hooks.json")
+}
+
+/// Hooks path with any symlink resolved, so writes land on the real file
+fn resolve_hooks_file() -> Result<PathBuf> {
+    let path = hooks_path();
+    let target = settings::resolve_target(&path)?;
+    if target != path {
+        println!(
+            "Cursor hooks.json is a symlink, writing to {}",
+            target.display()
+        );
+    }
+    Ok(target)
+}
+
+fn create_stop_hook() -> Value {
+    json!({
+        "command": format!(
+            "{} send --agent cursor {}",
+            config::bin_dir().join("ahoy").to_string_lossy(),
+            shell_quote(i18n::text(Phrase::TaskFinished))
+        )
+    })
+}
+
+pub fn install() -> Result<()> {
+    install_with(&InstallOptions::default())
+}
+
+pub fn install_with(options: &InstallOptions) -> Result<()> {
+    let hooks_file = resolve_hooks_file()?;
+
+    let existing = if hooks_file.exists() {
+        Some(fs::read_to_string(&hooks_file).context("Failed to read Cursor hooks.json")?)
+    } else {
+        None
+    };
+
+    let mut config: Value = if let Some(content) = &existing {
+        serde_json::from_str(content).context("Failed to parse Cursor hooks.json")?
+    } else {
+        if let Some(parent) = hooks_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        json!({ "version": 1 })
+    };
+
+    let config_obj = config
+        .as_object_mut()
+        .context("Cursor hooks.json is not a JSON object")?;
+
+    if !config_obj.contains_key("hooks") {
+        config_obj.insert("hooks".to_string(), json!({}));
+    }
+    let hooks = config_obj
+        .get_mut("hooks")
+        .and_then(|h| h.as_object_mut())
+        .context("hooks is not a JSON object")?;
+
+    if !hooks.contains_key("stop") {
+        hooks.insert("stop".to_string(), json!([]));
+    }
+    let stop_hooks = hooks
+        .get_mut("stop")
+        .and_then(|s| s.as_array_mut())
+        .context("stop is not a JSON array")?;
+
+    let already_installed = stop_hooks.iter().any(contains_ahoy_marker);
+
+    if already_installed && !options.replace {
+        println!("Ahoy hook is already installed for Cursor");
+        println!("Use --replace to upgrade the existing hook commands");
+        return Ok(());
+    }
+
+    // With --replace, drop existing ahoy hooks so the current command is re-added
+    let original_len = stop_hooks.len();
+    if options.replace {
+        stop_hooks.retain(|hook| !contains_ahoy_marker(hook));
+    }
+    let replaced = original_len - stop_hooks.len();
+
+    stop_hooks.push(create_stop_hook());
+
+    let content = settings::render(&config, options.settings_format, existing.as_deref())?;
+    fs::write(&hooks_file, &content).context("Failed to write Cursor hooks.json")?;
+
+    if replaced > 0 {
+        println!("Replaced {} existing ahoy hook(s) for Cursor", replaced);
+    }
+    println!("Installed ahoy hooks for Cursor:");
+    println!("  - stop: notifies when the agent finishes");
+    println!();
+    println!("Hooks file: {}", hooks_file.display());
+
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    let hooks_file = resolve_hooks_file()?;
+
+    if !hooks_file.exists() {
+        println!("Cursor hooks.json not found - nothing to uninstall");
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&hooks_file).context("Failed to read Cursor hooks.json")?;
+    let mut config: Value =
+        serde_json::from_str(&content).context("Failed to parse Cursor hooks.json")?;
+
+    let mut removed = false;
+
+    if let Some(stop_hooks) = config
+        .get_mut("hooks")
+        .and_then(|h| h.get_mut("stop"))
+        .and_then(|s| s.as_array_mut())
+    {
+        let original_len = stop_hooks.len();
+        stop_hooks.retain(|hook| !contains_ahoy_marker(hook));
+        removed = stop_hooks.len() < original_len;
+    }
+
+    if removed {
+        let content = settings::render(&config, SettingsFormat::Preserve, Some(&content))?;
+        fs::write(&hooks_file, &content).context("Failed to write Cursor hooks.json")?;
+        println!("Removed ahoy hooks from Cursor:");
+        println!("  - stop hook");
+    } else {
+        println!("Ahoy hooks were not installed for Cursor");
+    }
+
+    Ok(())
+}
+
+/// Cursor hooks are flat `{"command": ...}` entries, unlike Claude's matcher groups
+fn contains_ahoy_marker(hook: &Value) -> bool {
+    hook.get("command")
+        .and_then(|c| c.as_str())
+        .map(|cmd| cmd.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+pub fn is_installed() -> bool {
+    let Ok(content) = fs::read_to_string(hooks_path()) else {
+        return false;
+    };
+
+    let Ok(config) = serde_json::from_str::<Value>(&content) else {
+        return false;
+    };
+
+    config
+        .get("hooks")
+        .and_then(|h| h.get("stop"))
+        .and_then(|s| s.as_array())
+        .map(|arr| arr.iter().any(contains_ahoy_marker))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_ahoy_marker_true() {
+        let hook = json!({ "command": "/path/to/ahoy send --agent cursor 'Task finished'" });
+
+        assert!(contains_ahoy_marker(&hook));
+    }
+
+    #[test]
+    fn test_contains_ahoy_marker_false() {
+        let hook = json!({ "command": "/usr/bin/other-command" });
+
+        assert!(!contains_ahoy_marker(&hook));
+    }
+
+    #[test]
+    fn test_contains_ahoy_marker_missing_command() {
+        let hook = json!({});
+
+        assert!(!contains_ahoy_marker(&hook));
+    }
+
+    #[test]
+    fn test_create_stop_hook_format() {
+        let hook = create_stop_hook();
+
+        let command = hook["command"].as_str().unwrap();
+        assert!(command.contains("ahoy"));
+        assert!(command.contains("--agent cursor"));
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::PathBuf;
+
+use super::claude::{contains_ahoy_marker, shell_quote};
+use super::settings::{self, SettingsFormat};
+use super::setup::InstallOptions;
+use crate::config;
+use crate::i18n::{self, Phrase};
+
+/// Hook events ahoy registers, with the phrase each one notifies with.
+/// Gemini CLI uses the same matcher/hooks layout as Claude Code.
+const HOOK_EVENTS: [(&str, Phrase); 2] = [
+    ("AfterAgent", Phrase::TaskFinished),
+    ("Notification", Phrase::NeedsPermission),
+];
+
+fn settings_path() -> PathBuf {
+    // Allow test override via env var
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return PathBuf::from(test_home).join(".gemini/settings.json");
+    }
+
+    dirs::home_dir()
+        .expect("Could not determine home directory")
+        .join(".gemini/settings.json")
+}
+
+/// Settings path with any symlink resolved, so writes land on the real file
+fn resolve_settings_file() -> Result<PathBuf> {
+    let path = settings_path();
+    let target = settings::resolve_target(&path)?;
+    if target != path {
+        println!(
+            "Gemini settings.json is a symlink, writing to {}",
+            target.display()
+        );
+    }
+    Ok(target)
+}
+
+fn create_hook(phrase: Phrase) -> Value {
+    json!({
+        "matcher": "*",
+        "hooks": [
+            {
+                "type": "command",
+                "command": format!(
+                    "{} send --agent gemini {}",
+                    config::bin_dir().join("ahoy").to_string_lossy(),
+                    shell_quote(i18n::text(phrase))
+                ),
+                "timeout": 5000
+            }
+        ]
+    })
+}
+
+pub fn install() -> Result<()> {
+    install_with(&InstallOptions::default())
+}
+
+pub fn install_with(options: &InstallOptions) -> Result<()> {
+    let settings_file = resolve_settings_file()?;
+
+    let existing = if settings_file.exists() {
+        Some(fs::read_to_string(&settings_file).context("Failed to read Gemini settings.json")?)
+    } else {
+        None
+    };
+
+    let mut settings: Value = if let Some(content) = &existing {
+        serde_json::from_str(content).context("Failed to parse Gemini settings.json")?
+    } else {
+        if let Some(parent) = settings_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        json!({})
+    };
+
+    let settings_obj = settings
+        .as_object_mut()
+        .context("Gemini settings.json is not a JSON object")?;
+
+    if !settings_obj.contains_key("hooks") {
+        settings_obj.insert("hooks".to_string(), json!({}));
+    }
+    let hooks = settings_obj
+        .get_mut("hooks")
+        .and_then(|h| h.as_object_mut())
+        .context("hooks is not a JSON object")?;
+
+    let already_installed = hooks
+        .get("AfterAgent")
+        .and_then(|h| h.as_array())
+        .is_some_and(|arr| arr.iter().any(contains_ahoy_marker));
+
+    if already_installed && !options.replace {
+        println!("Ahoy hook is already installed for Gemini CLI");
+        println!("Use --replace to upgrade the existing hook commands");
+        return Ok(());
+    }
+
+    let mut replaced = 0;
+    for (event, phrase) in HOOK_EVENTS {
+        let event_hooks = hooks
+            .entry(event)
+            .or_insert_with(|| json!([]))
+            .as_array_mut()
+            .with_context(|| format!("{} is not a JSON array", event))?;
+
+        // With --replace, drop existing ahoy hooks so the current commands are re-added
+        if options.replace {
+            let original_len = event_hooks.len();
+            event_hooks.retain(|hook| !contains_ahoy_marker(hook));
+            replaced += original_len - event_hooks.len();
+        }
+
+        event_hooks.push(create_hook(phrase));
+    }
+
+    let content = settings::render(&settings, options.settings_format, existing.as_deref())?;
+    fs::write(&settings_file, &content).context("Failed to write Gemini settings.json")?;
+
+    if replaced > 0 {
+        println!("Replaced {} existing ahoy hook(s) for Gemini CLI", replaced);
+    }
+    println!("Installed ahoy hooks for Gemini CLI:");
+    println!("  - AfterAgent: notifies when Gemini finishes");
+    println!("  - Notification: notifies when permission is needed");
+    println!();
+    println!("Settings file: {}", settings_file.display());
+
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    let settings_file = resolve_settings_file()?;
+
+    if !settings_file.exists() {
+        println!("Gemini settings.json not found - nothing to uninstall");
+        return Ok(());
+    }
+
+    let content =
+        fs::read_to_string(&settings_file).context("Failed to read Gemini settings.json")?;
+    let mut settings: Value =
+        serde_json::from_str(&content).context("Failed to parse Gemini settings.json")?;
+
+    let mut removed = Vec::new();
+
+    if let Some(hooks) = settings.get_mut("hooks").and_then(|h| h.as_object_mut()) {
+        for (event, _) in HOOK_EVENTS {
+            if let Some(event_hooks) = hooks.get_mut(event).and_then(|s| s.as_array_mut()) {
+                let original_len = event_hooks.len();
+                event_hooks.retain(|hook| !contains_ahoy_marker(hook));
+                if event_hooks.len() < original_len {
+                    removed.push(event);
+                }
+            }
+        }
+    }
+
+    if removed.is_empty() {
+        println!("Ahoy hooks were not installed for Gemini CLI");
+        return Ok(());
+    }
+
+    let content = settings::render(&settings, SettingsFormat::Preserve, Some(&content))?;
+    fs::write(&settings_file, &content).context("Failed to write Gemini settings.json")?;
+    println!("Removed ahoy hooks from Gemini CLI:");
+    for event in removed {
+        println!("  - {} hook", event);
+    }
+
+    Ok(())
+}
+
+pub fn is_installed() -> bool {
+    let Ok(content) = fs::read_to_string(settings_path()) else {
+        return false;
+    };
+
+    let Ok(settings) = serde_json::from_str::<Value>(&content) else {
+        return false;
+    };
+
+    settings
+        .get("hooks")
+        .and_then(|h| h.get("AfterAgent"))
+        .and_then(|s| s.as_array())
+        .is_some_and(|arr| arr.iter().any(contains_ahoy_marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_hook_format() {
+        let hook = create_hook(Phrase::TaskFinished);
+
+        assert_eq!(hook["matcher"], "*");
+
+        let hooks_array = hook["hooks"].as_array().unwrap();
+        assert_eq!(hooks_array.len(), 1);
+        assert_eq!(hooks_array[0]["type"], "command");
+
+        let command = hooks_array[0]["command"].as_str().unwrap();
+        assert!(command.contains("ahoy"));
+        assert!(command.contains("--agent gemini"));
+        assert!(contains_ahoy_marker(&hook));
+    }
+}
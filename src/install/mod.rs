@@ -1,4 +1,9 @@
+pub mod aider;
 pub mod claude;
-pub mod install;
+pub mod codex;
+pub mod cursor;
+pub mod gemini;
+pub mod settings;
+pub mod setup;
 pub mod status;
 pub mod uninstall;
@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::{PrettyFormatter, Serializer};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How to format a settings file when writing it back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SettingsFormat {
+    /// Two-space indented JSON
+    Pretty,
+    /// Minified JSON on a single line
+    Compact,
+    /// Match the existing file's indentation (pretty if there is no file)
+    #[default]
+    Preserve,
+}
+
+/// Serialize settings in the requested format.
+///
+/// `existing` is the current file content, used to detect the style for `Preserve`.
+pub fn render(settings: &Value, format: SettingsFormat, existing: Option<&str>) -> Result<String> {
+    let content = match format {
+        SettingsFormat::Pretty => serde_json::to_string_pretty(settings)?,
+        SettingsFormat::Compact => serde_json::to_string(settings)?,
+        SettingsFormat::Preserve => match existing.map(detect_indent) {
+            Some(Some(indent)) => to_string_with_indent(settings, &indent)?,
+            Some(None) => serde_json::to_string(settings)?,
+            None => serde_json::to_string_pretty(settings)?,
+        },
+    };
+
+    // Keep a trailing newline if the existing file had one
+    if format == SettingsFormat::Preserve && existing.is_some_and(|e| e.ends_with('\n')) {
+        return Ok(content + "\n");
+    }
+
+    Ok(content)
+}
+
+/// Resolve the file a settings path really refers to.
+///
+/// Settings files are often symlinked into a dotfiles repo. Writing to the
+/// resolved target keeps the link intact and makes it clear where changes land.
+pub fn resolve_target(path: &Path) -> Result<PathBuf> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(path)
+            .with_context(|| format!("{} is a symlink to a missing file", path.display())),
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+/// Detect the indentation used by a JSON document, or None if it's on one line
+fn detect_indent(content: &str) -> Option<String> {
+    content.trim().lines().skip(1).find_map(|line| {
+        let indent: String = line
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        (!indent.is_empty()).then_some(indent)
+    })
+}
+
+fn to_string_with_indent(settings: &Value, indent: &str) -> Result<String> {
+    let mut buf = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = Serializer::with_formatter(&mut buf, formatter);
+    settings.serialize(&mut serializer)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_pretty() {
+        let content = render(&json!({"a": 1}), SettingsFormat::Pretty, None).unwrap();
+        assert_eq!(content, "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn test_render_compact() {
+        let content = render(&json!({"a": 1}), SettingsFormat::Compact, None).unwrap();
+        assert_eq!(content, "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_render_preserve_without_existing_file_is_pretty() {
+        let content = render(&json!({"a": 1}), SettingsFormat::Preserve, None).unwrap();
+        assert_eq!(content, "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn test_render_preserve_minified() {
+        let content = render(
+            &json!({"a": 1}),
+            SettingsFormat::Preserve,
+            Some("{\"b\":2}"),
+        )
+        .unwrap();
+        assert_eq!(content, "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_render_preserve_four_space_indent() {
+        let existing = "{\n    \"b\": 2\n}\n";
+        let content = render(&json!({"a": 1}), SettingsFormat::Preserve, Some(existing)).unwrap();
+        assert_eq!(content, "{\n    \"a\": 1\n}\n");
+    }
+
+    #[test]
+    fn test_render_preserve_tab_indent() {
+        let existing = "{\n\t\"b\": 2\n}";
+        let content = render(&json!({"a": 1}), SettingsFormat::Preserve, Some(existing)).unwrap();
+        assert_eq!(content, "{\n\t\"a\": 1\n}");
+    }
+}
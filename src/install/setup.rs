@@ -0,0 +1,121 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::settings::SettingsFormat;
+use super::{aider, claude, codex, cursor, gemini};
+
+/// Options controlling how hooks are installed
+#[derive(Debug, Default)]
+pub struct InstallOptions {
+    /// Output format for rewritten settings files
+    pub settings_format: SettingsFormat,
+    /// Remove existing ahoy hooks and re-add the current ones
+    pub replace: bool,
+}
+
+/// An agent `install all` picks up when its config directory exists
+struct Detectable {
+    name: &'static str,
+    /// Directory under home whose presence means the agent is installed
+    dir: &'static str,
+    install: fn(&InstallOptions) -> Result<()>,
+}
+
+// Aider is left out: its wrapper shadows the real binary on PATH, so it is
+// only installed when asked for by name
+const DETECTABLE: &[Detectable] = &[
+    Detectable {
+        name: "Claude Code",
+        dir: ".claude",
+        install: claude::install_with,
+    },
+    Detectable {
+        name: "Codex",
+        dir: ".codex",
+        install: codex::install_with,
+    },
+    Detectable {
+        name: "Gemini CLI",
+        dir: ".gemini",
+        install: gemini::install_with,
+    },
+    Detectable {
+        name: "Cursor",
+        dir: ".cursor",
+        install: cursor::install_with,
+    },
+];
+
+fn agent_home() -> Option<PathBuf> {
+    // Allow test override via env var
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return Some(PathBuf::from(test_home));
+    }
+    dirs::home_dir()
+}
+
+/// Names of the agents whose config directory exists under `home`
+pub fn detect_agents(home: &Path) -> Vec<&'static str> {
+    DETECTABLE
+        .iter()
+        .filter(|agent| home.join(agent.dir).is_dir())
+        .map(|agent| agent.name)
+        .collect()
+}
+
+/// Install hooks for every detected agent, skipping ones that aren't present
+fn install_detected(options: &InstallOptions) -> Result<()> {
+    let detected = agent_home()
+        .map(|home| detect_agents(&home))
+        .unwrap_or_default();
+    if detected.is_empty() {
+        println!("No supported agents detected, nothing to install");
+        return Ok(());
+    }
+
+    println!("Detected: {}", detected.join(", "));
+    println!();
+
+    // One broken settings file shouldn't stop the other agents from being set up
+    let mut installed = Vec::new();
+    let mut failed = Vec::new();
+    for agent in DETECTABLE.iter().filter(|a| detected.contains(&a.name)) {
+        println!("[{}]", agent.name);
+        match (agent.install)(options) {
+            Ok(()) => installed.push(agent.name),
+            Err(e) => {
+                println!("Failed: {:#}", e);
+                failed.push(agent.name);
+            }
+        }
+        println!();
+    }
+
+    if !installed.is_empty() {
+        println!("Installed hooks for: {}", installed.join(", "));
+    }
+    if !failed.is_empty() {
+        println!("Failed to install hooks for: {}", failed.join(", "));
+        anyhow::bail!("Failed to install hooks for {} agent(s)", failed.len());
+    }
+    Ok(())
+}
+
+pub fn run(agent: Option<String>, options: InstallOptions) -> Result<()> {
+    let agent = agent.unwrap_or_else(|| "all".to_string());
+
+    match agent.as_str() {
+        "claude" => claude::install_with(&options),
+        "codex" => codex::install_with(&options),
+        "gemini" => gemini::install_with(&options),
+        "cursor" => cursor::install_with(&options),
+        "aider" => aider::install_with(&options),
+        "all" => install_detected(&options),
+        other => {
+            anyhow::bail!(
+                "Unknown agent: {}. Supported: claude, codex, gemini, cursor, aider, all",
+                other
+            );
+        }
+    }
+}
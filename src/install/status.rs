@@ -1,6 +1,6 @@
 use anyhow::Result;
 
-use super::claude;
+use super::{aider, claude, codex, cursor, gemini};
 
 pub fn run() -> Result<()> {
     println!("Installed hooks:");
@@ -16,11 +16,45 @@ pub fn run() -> Result<()> {
     };
     println!("  [{}] Claude Code ({})", claude_marker, claude_status);
 
-    // Codex (placeholder)
-    println!("  [ ] Codex (not yet supported)");
+    // Codex
+    let codex_installed = codex::is_installed();
+    let codex_marker = if codex_installed { "x" } else { " " };
+    let codex_status = if codex_installed {
+        "installed"
+    } else {
+        "not installed"
+    };
+    println!("  [{}] Codex ({})", codex_marker, codex_status);
+
+    // Gemini
+    let gemini_installed = gemini::is_installed();
+    let gemini_marker = if gemini_installed { "x" } else { " " };
+    let gemini_status = if gemini_installed {
+        "installed"
+    } else {
+        "not installed"
+    };
+    println!("  [{}] Gemini CLI ({})", gemini_marker, gemini_status);
 
-    // Gemini (placeholder)
-    println!("  [ ] Gemini CLI (not yet supported)");
+    // Cursor
+    let cursor_installed = cursor::is_installed();
+    let cursor_marker = if cursor_installed { "x" } else { " " };
+    let cursor_status = if cursor_installed {
+        "installed"
+    } else {
+        "not installed"
+    };
+    println!("  [{}] Cursor ({})", cursor_marker, cursor_status);
+
+    // Aider
+    let aider_installed = aider::is_installed();
+    let aider_marker = if aider_installed { "x" } else { " " };
+    let aider_status = if aider_installed {
+        "wrapper installed"
+    } else {
+        "not installed"
+    };
+    println!("  [{}] Aider ({})", aider_marker, aider_status);
 
     Ok(())
 }
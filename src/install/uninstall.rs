@@ -1,20 +1,20 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
 
-use super::claude;
+use super::{aider, claude, codex, cursor, gemini};
+use crate::config;
 
 pub fn run(agent: Option<String>) -> Result<()> {
     let agent = agent.unwrap_or_else(|| "all".to_string());
 
     match agent.as_str() {
         "claude" => claude::uninstall(),
-        "codex" => {
-            println!("Codex hook uninstall not yet implemented");
-            Ok(())
-        }
-        "gemini" => {
-            println!("Gemini hook uninstall not yet implemented");
-            Ok(())
-        }
+        "codex" => codex::uninstall(),
+        "gemini" => gemini::uninstall(),
+        "cursor" => cursor::uninstall(),
+        "aider" => aider::uninstall(),
         "all" => {
             println!("Uninstalling hooks from all agents...");
             println!();
@@ -24,14 +24,75 @@ pub fn run(agent: Option<String>) -> Result<()> {
             claude::uninstall()?;
             println!();
 
-            // TODO: Add codex and gemini when implemented
+            // Codex
+            println!("[Codex]");
+            codex::uninstall()?;
+            println!();
+
+            // Gemini CLI
+            println!("[Gemini CLI]");
+            gemini::uninstall()?;
+            println!();
+
+            // Cursor
+            println!("[Cursor]");
+            cursor::uninstall()?;
+            println!();
+
+            // Aider
+            println!("[Aider]");
+            aider::uninstall()?;
+            println!();
             Ok(())
         }
         other => {
             anyhow::bail!(
-                "Unknown agent: {}. Supported: claude, codex, gemini, all",
+                "Unknown agent: {}. Supported: claude, codex, gemini, cursor, aider, all",
                 other
             );
         }
     }
 }
+
+/// Directories `--purge` deletes
+fn purge_dirs() -> Vec<PathBuf> {
+    // Allow test override via env var, ignoring the real XDG variables
+    if let Ok(test_home) = std::env::var("AHOY_TEST_HOME") {
+        return config::owned_dirs(&PathBuf::from(test_home), |_| None);
+    }
+
+    let home = dirs::home_dir().expect("Could not determine home directory");
+    config::owned_dirs(&home, |name| std::env::var_os(name))
+}
+
+/// Remove hooks from every agent, then delete ahoy's own directories.
+/// Unless `yes` is set, asks for confirmation on `input` first.
+pub fn purge(yes: bool, input: &mut impl BufRead) -> Result<()> {
+    let dirs: Vec<PathBuf> = purge_dirs().into_iter().filter(|d| d.exists()).collect();
+
+    if !yes {
+        println!("This removes ahoy hooks from every agent and deletes:");
+        for dir in &dirs {
+            println!("  {}", dir.display());
+        }
+        print!("Continue? [y/N] ");
+        std::io::stdout().flush()?;
+
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Aborted, nothing was removed");
+            return Ok(());
+        }
+        println!();
+    }
+
+    // Hooks first: the Aider wrapper lives in ~/.ahoy/bin
+    run(Some("all".to_string()))?;
+
+    for dir in &dirs {
+        fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+        println!("Removed {}", dir.display());
+    }
+    Ok(())
+}
@@ -1,5 +1,39 @@
-// Expose modules for testing and potential library use
+//! Desktop notifications for LLM coding agents.
+//!
+//! The items re-exported at the crate root are the library API and follow
+//! semver: [`Notification`] and its [`Urgency`] and [`NotificationAction`],
+//! [`send`] and [`show`]. The modules below are public so the `ahoy` binary
+//! and the integration tests can reach them. They may change in any release.
+//!
+//! ```no_run
+//! use ahoy::{Notification, Urgency};
+//!
+//! let notification = Notification::new("Build bot", "Release 1.4 is ready")
+//!     .with_urgency(Urgency::Critical)
+//!     .with_url("https://example.com/releases/1.4");
+//!
+//! // Shows it on this machine and on any [forward]/[pushover] destinations
+//! // in the user's config.toml
+//! ahoy::send(&notification)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
 pub mod client;
 pub mod config;
+pub mod doctor;
+pub mod i18n;
 pub mod install;
 pub mod notify;
+
+pub use client::message::{Notification, NotificationAction, Urgency};
+pub use notify::show;
+
+/// Deliver a notification as `ahoy send` would, using the user's config for
+/// remote destinations and recording it in the history. Unlike the CLI, it
+/// isn't subject to quiet hours, cooldowns, throttling or rules.
+///
+/// Use [`show`] to display it on this machine only.
+pub fn send(notification: &Notification) -> anyhow::Result<()> {
+    let config = config::load()?;
+    client::send::deliver(notification, &config)
+}
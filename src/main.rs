@@ -1,15 +1,37 @@
-use ahoy::{client, install};
-use clap::{Parser, Subcommand};
+use ahoy::client::agent::Agent;
+use ahoy::client::history;
+use ahoy::client::message::{NotificationAction, Urgency};
+use ahoy::client::send::{HookFormat, SendOptions, SummarySource};
+use ahoy::config::{self, Config};
+use ahoy::install::settings::SettingsFormat;
+use ahoy::install::setup::InstallOptions;
+use ahoy::{client, doctor, install};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+
+/// Flag that switches error output to JSON, looked for in argv when clap fails to parse
+const JSON_ERRORS_FLAG: &str = "--json-errors";
 
 #[derive(Parser)]
 #[command(name = "ahoy")]
 #[command(about = "Cross-platform notification CLI for LLM coding agents")]
 #[command(version)]
 struct Cli {
+    /// Print errors to stderr as JSON ({"error": "...", "code": N}) for scripting
+    #[arg(long, global = true)]
+    json_errors: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+// Parsed once at startup, so the size of the Send variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Send a notification
@@ -17,69 +39,407 @@ enum Commands {
         /// The notification message
         message: Option<String>,
 
-        /// Notification title
-        #[arg(short, long, default_value = "Ahoy")]
-        title: String,
+        /// Notification title [default: Ahoy, or the agent's name with --agent]
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// Second line between the title and body (shown above the body where
+        /// there's no subtitle line)
+        #[arg(long)]
+        subtitle: Option<String>,
 
         /// Send raw JSON message
         #[arg(long)]
         json: Option<String>,
 
+        /// Read a notification JSON document (as for --json) from stdin
+        #[arg(long, conflicts_with_all = ["message", "json", "hook", "from_codex"])]
+        json_stdin: bool,
+
         /// Read Claude Code hook data from stdin to extract last prompt
-        #[arg(long)]
+        #[arg(long, group = "hook")]
         from_claude: bool,
 
-        /// Bundle ID to activate when notification is clicked
+        /// Read Gemini CLI hook data from stdin to extract last prompt (same as --format gemini)
+        #[arg(long, group = "hook")]
+        from_gemini: bool,
+
+        /// Read hook data in this agent's format from stdin
+        #[arg(long, value_enum, group = "hook")]
+        format: Option<HookFormat>,
+
+        /// Treat MESSAGE as the JSON payload Codex passes to its `notify` program
+        #[arg(long, conflicts_with_all = ["hook", "json"])]
+        from_codex: bool,
+
+        /// Bundle ID to activate when notification is clicked. Repeat or comma-separate
+        /// to give fallbacks; the first one that's running is activated
+        #[arg(long, value_delimiter = ',')]
+        activate: Vec<String>,
+
+        /// Add a button as ID:LABEL[:COMMAND]; the command runs in a shell when the
+        /// button is chosen. Repeat for more buttons
+        #[arg(long = "action", value_name = "ID:LABEL[:COMMAND]")]
+        actions: Vec<NotificationAction>,
+
+        /// URL to open when notification is clicked (takes precedence over --activate)
         #[arg(long)]
-        activate: Option<String>,
+        link: Option<String>,
+
+        /// Icon to show: an image path or an icon theme name
+        #[arg(long, value_name = "NAME|PATH")]
+        icon: Option<String>,
+
+        /// System sound to play by name (e.g. Ping, Submarine), or "none" for silence
+        #[arg(long)]
+        sound: Option<String>,
+
+        /// Custom sound file to play (macOS: .aiff, .wav or .caf, copied into ~/Library/Sounds)
+        #[arg(long)]
+        sound_file: Option<String>,
+
+        /// How long the notification stays visible in milliseconds; 0 keeps it until
+        /// dismissed. Ignored on macOS, where the user's alert style decides
+        #[arg(long, value_name = "MS")]
+        timeout: Option<u32>,
+
+        /// Replace the previous notification sent with the same KEY instead of adding
+        /// another, so progress updates overwrite each other
+        #[arg(long = "replace", value_name = "KEY")]
+        replace_id: Option<String>,
+
+        /// Apply the default title for an agent
+        #[arg(long, value_enum)]
+        agent: Option<Agent>,
+
+        /// Notification urgency: low, normal, critical. Also accepts ntfy-style
+        /// min/default/high/max/urgent and numeric priorities from -2 to 2
+        #[arg(long, visible_alias = "priority", allow_negative_numbers = true)]
+        urgency: Option<Urgency>,
+
+        /// Set urgency from a wrapped command's exit code (0 = normal, otherwise critical)
+        ///
+        /// Without --title, the title becomes "Succeeded" or "Failed (exit N)".
+        /// Typical usage after a long-running command:
+        ///
+        ///   make test; ahoy send --urgency-from-exit $? "make test"
+        #[arg(
+            long,
+            value_name = "CODE",
+            allow_negative_numbers = true,
+            verbatim_doc_comment
+        )]
+        urgency_from_exit: Option<i32>,
+
+        /// Strip Markdown formatting (emphasis, inline code, links) from the body
+        #[arg(long)]
+        strip_markdown: bool,
+
+        /// With hook data on stdin, summarize the last tool result instead of the last prompt
+        #[arg(long, requires = "hook")]
+        include_tool_result: bool,
+
+        /// With hook data on stdin, shorten long text to its first sentence instead of truncating
+        #[arg(long, requires = "hook")]
+        summary_only: bool,
+
+        /// With hook data on stdin, whose last message to show: your prompt or the agent's reply
+        #[arg(long, value_enum, default_value_t, requires = "hook")]
+        summary: SummarySource,
+
+        /// With hook data on stdin, number of lines of the message to include, joined with " / "
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "hook")]
+        body_lines: usize,
+
+        /// With hook data on stdin, fail on empty or incomplete data instead of
+        /// falling back to a generic message
+        #[arg(long, requires = "hook")]
+        strict: bool,
+
+        /// With hook data on stdin, body template; {project}, {cwd}, {tool}, {prompt}
+        /// and {message} are filled in, and --title may use them too
+        /// [default: "[{project}] {message}"]
+        #[arg(long, requires = "hook")]
+        template: Option<String>,
+
+        /// Transcript file to watch; notify once it has been idle for --notify-on-idle seconds
+        #[arg(long, value_name = "PATH", requires = "notify_on_idle")]
+        watch_transcript: Option<PathBuf>,
+
+        /// With --watch-transcript, seconds without transcript activity before notifying
+        #[arg(long, value_name = "SECS", requires = "watch_transcript")]
+        notify_on_idle: Option<u64>,
+
+        /// Drop this notification if another was shown less than MS milliseconds ago
+        /// (critical notifications are never dropped)
+        #[arg(long, value_name = "MS")]
+        cooldown: Option<u64>,
+
+        /// Show this notification even during configured quiet hours
+        #[arg(long)]
+        force: bool,
+
+        /// Exit non-zero if any remote backend fails, not only the local notification
+        #[arg(long)]
+        exit_on_failure: bool,
+
+        /// Print the resolved notification as JSON instead of sending it
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Install hooks for LLM CLI agents
     Install {
-        /// Agent to install hook for (claude, codex, gemini)
+        /// Agent to install hook for (claude, codex, gemini, cursor, aider)
         agent: Option<String>,
 
         /// Show installation status
         #[arg(long)]
         status: bool,
+
+        /// How to format rewritten settings files
+        #[arg(long, value_enum, default_value_t = SettingsFormat::Preserve)]
+        settings_format: SettingsFormat,
+
+        /// Replace existing ahoy hooks with the current hook commands
+        #[arg(long)]
+        replace: bool,
     },
 
     /// Remove hooks from LLM CLI agents
     Uninstall {
-        /// Agent to uninstall hook from (claude, codex, gemini, or all)
+        /// Agent to uninstall hook from (claude, codex, gemini, cursor, aider, or all)
         agent: Option<String>,
+
+        /// Also delete ahoy's config, history and state, after asking
+        #[arg(long, conflicts_with = "agent")]
+        purge: bool,
+
+        /// Don't ask before purging
+        #[arg(short, long, requires = "purge")]
+        yes: bool,
+    },
+
+    /// Show recently delivered notifications
+    History {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Only show notifications newer than this age (e.g. 30m, 2h, 7d)
+        #[arg(long, value_name = "AGE", value_parser = history::parse_age)]
+        since: Option<Duration>,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check the setup and suggest fixes for anything that would stop notifications
+    Doctor,
+
+    /// Print a shell completion script for ahoy to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
     },
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> ExitCode {
+    // Help and version output still go through clap as usual
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Without a parsed command line, fall back to spotting the flag in argv
+        Err(e) if e.use_stderr() && std::env::args().any(|arg| arg == JSON_ERRORS_FLAG) => {
+            // The first rendered line is clap's "error: ..." summary
+            let rendered = e.render().to_string();
+            let message = rendered
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string();
+            return report_error(&message, e.exit_code(), true);
+        }
+        Err(e) => e.exit(),
+    };
+
+    let json_errors = cli.json_errors;
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if json_errors => report_error(&format!("{:#}", e), 1, true),
+        Err(e) => report_error(&format!("{:?}", e), 1, false),
+    }
+}
+
+/// Print an error to stderr, as JSON when requested, and return its exit code
+fn report_error(message: &str, code: i32, json: bool) -> ExitCode {
+    if json {
+        eprintln!("{}", serde_json::json!({ "error": message, "code": code }));
+    } else {
+        eprintln!("Error: {}", message);
+    }
+    ExitCode::from(u8::try_from(code).unwrap_or(1))
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    // Completions don't depend on config, so a broken config file can't break them
+    if let Commands::Completions { shell } = cli.command {
+        clap_complete::generate(shell, &mut Cli::command(), "ahoy", &mut std::io::stdout());
+        return Ok(());
+    }
+    // Doctor reports a broken config file itself, so it must not depend on loading one
+    if let Commands::Doctor = cli.command {
+        return doctor::run();
+    }
+
+    // Logging isn't set up until the config is loaded, so report this afterwards
+    let migrated = config::migrate();
+    // Only send reads the config, so a broken file can't stop install, uninstall or history
+    let config = if matches!(cli.command, Commands::Send { .. }) {
+        config::load()?
+    } else {
+        Config::default()
+    };
+
+    // The configured level replaces the INFO default; RUST_LOG directives still apply
+    let configured_level = config.log_level.as_deref().unwrap_or("info");
+    let (log_level, invalid_level) = match configured_level.parse() {
+        Ok(directive) => (directive, None),
+        Err(_) => (LevelFilter::INFO.into(), Some(configured_level)),
+    };
+    // Logs go to stderr so stdout stays clean for --dry-run and --json output
     tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
+        .with_writer(std::io::stderr)
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env().add_directive(log_level))
         .init();
 
-    let cli = Cli::parse();
+    if let Some(level) = invalid_level {
+        warn!("Invalid log_level in config: {}, using info", level);
+    }
+    let unknown = config.unknown_keys();
+    if !unknown.is_empty() {
+        warn!(
+            "Ignoring unknown keys in {}: {}",
+            config::config_path().display(),
+            unknown.join(", ")
+        );
+    }
+    match migrated {
+        Ok(moved) => {
+            for path in moved {
+                info!("Moved {} to its XDG location", path.display());
+            }
+        }
+        Err(e) => warn!("Could not move files out of ~/.ahoy: {:#}", e),
+    }
 
     match cli.command {
         Commands::Send {
             message,
             title,
+            subtitle,
             json,
+            json_stdin,
             from_claude,
+            from_gemini,
+            format,
+            from_codex,
             activate,
+            actions,
+            link,
+            icon,
+            sound,
+            sound_file,
+            timeout,
+            replace_id,
+            agent,
+            urgency,
+            urgency_from_exit,
+            strip_markdown,
+            include_tool_result,
+            summary_only,
+            summary,
+            body_lines,
+            strict,
+            template,
+            watch_transcript,
+            notify_on_idle,
+            cooldown,
+            force,
+            exit_on_failure,
+            dry_run,
         } => {
-            client::send::run(message, title, json, from_claude, activate)?;
+            let hook_format = format
+                .or(from_claude.then_some(HookFormat::Claude))
+                .or(from_gemini.then_some(HookFormat::Gemini));
+            client::send::run(
+                SendOptions {
+                    message,
+                    title,
+                    subtitle,
+                    json,
+                    json_stdin,
+                    hook_format,
+                    from_codex,
+                    activate,
+                    actions,
+                    link,
+                    icon,
+                    sound,
+                    sound_file,
+                    timeout_ms: timeout,
+                    replace_id,
+                    agent,
+                    urgency,
+                    urgency_from_exit,
+                    strip_markdown,
+                    include_tool_result,
+                    summary_only,
+                    summary,
+                    body_lines,
+                    strict,
+                    template,
+                    watch_transcript,
+                    notify_on_idle_secs: notify_on_idle,
+                    cooldown_ms: cooldown,
+                    force,
+                    exit_on_failure,
+                    dry_run,
+                },
+                &config,
+            )?;
         }
-        Commands::Install { agent, status } => {
+        Commands::Install {
+            agent,
+            status,
+            settings_format,
+            replace,
+        } => {
             if status {
                 install::status::run()?;
             } else {
-                install::install::run(agent)?;
+                install::setup::run(
+                    agent,
+                    InstallOptions {
+                        settings_format,
+                        replace,
+                    },
+                )?;
             }
         }
-        Commands::Uninstall { agent } => {
-            install::uninstall::run(agent)?;
+        Commands::Uninstall { agent, purge, yes } => {
+            if purge {
+                install::uninstall::purge(yes, &mut std::io::stdin().lock())?;
+            } else {
+                install::uninstall::run(agent)?;
+            }
+        }
+        Commands::History { limit, since, json } => {
+            history::run(limit, since, json)?;
+        }
+        Commands::Doctor | Commands::Completions { .. } => {
+            unreachable!("handled before loading config")
         }
     }
 
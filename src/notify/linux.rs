@@ -1,6 +1,16 @@
 use anyhow::Result;
 
 use crate::client::message::Notification;
+use crate::notify::NotificationBackend;
+
+/// Shows notifications through the desktop notification service
+pub struct LinuxBackend;
+
+impl NotificationBackend for LinuxBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()> {
+        show(notification)
+    }
+}
 
 pub fn show(_notification: &Notification) -> Result<()> {
     anyhow::bail!("Linux notifications not yet implemented")
@@ -1,36 +1,106 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
 use std::process::Command;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::client::message::Notification;
+use crate::client::message::{Notification, SILENT_SOUND};
+use crate::config;
+use crate::notify::{self, Icon, NotificationBackend};
+
+const DEFAULT_SOUND: &str = "Glass";
+
+/// Prefix of the line the helper prints to stdout when an action button is chosen
+const ACTION_PREFIX: &str = "action:";
+
+/// Sound file formats that macOS can play as a notification sound
+const SUPPORTED_SOUND_EXTENSIONS: &[&str] = &["aiff", "aif", "aifc", "wav", "caf"];
+
+/// Shows notifications through the Ahoy.app helper
+pub struct MacosBackend;
+
+impl NotificationBackend for MacosBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()> {
+        show(notification)
+    }
+}
 
 pub fn show(notification: &Notification) -> Result<()> {
     info!("Attempting to show macOS notification via ahoy-notify...");
 
     // Use our Swift helper binary for native macOS notifications
     // The helper is inside the Ahoy.app bundle for proper icon display
-    let ahoy_notify = dirs::home_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?
-        .join(".ahoy")
-        .join("Ahoy.app")
-        .join("Contents")
-        .join("MacOS")
-        .join("ahoy-notify");
+    let ahoy_notify = config::notify_helper_path();
+
+    // "none" silences even a custom sound file; the helper falls back to the
+    // default for sound names macOS doesn't know
+    let sound = if notification.is_silent() {
+        SILENT_SOUND.to_string()
+    } else if let Some(ref path) = notification.sound_file {
+        register_sound_file(Path::new(path)).unwrap_or_else(|e| {
+            warn!("Could not register sound file {}: {:#}", path, e);
+            DEFAULT_SOUND.to_string()
+        })
+    } else {
+        notification
+            .sound
+            .clone()
+            .unwrap_or_else(|| DEFAULT_SOUND.to_string())
+    };
+
+    if let Some(timeout_ms) = notification.timeout_ms {
+        info!(
+            "Ignoring timeout of {}ms: macOS doesn't support timed dismissal",
+            timeout_ms
+        );
+    }
 
     let mut cmd = Command::new(&ahoy_notify);
     cmd.arg(&notification.title)
         .arg(&notification.body)
         .arg("--sound")
-        .arg("Glass");
+        .arg(&sound);
+
+    // The app icon already shows on the left, so only a chosen icon is attached
+    if notification.icon.is_some()
+        && let Icon::File(path) =
+            notify::resolve_icon(notification.icon.as_deref(), &config::icons_dir())
+        && path.is_file()
+    {
+        cmd.arg("--icon").arg(path);
+    }
+
+    if let Some(ref subtitle) = notification.subtitle {
+        cmd.arg("--subtitle").arg(subtitle);
+    }
 
-    if let Some(ref bundle_id) = notification.activate {
+    if let Some(ref key) = notification.replace_id {
+        cmd.arg("--replace-id").arg(key);
+    }
+
+    for bundle_id in &notification.activate {
         cmd.arg("--activate").arg(bundle_id);
     }
 
+    if let Some(urgency) = notification.urgency {
+        cmd.arg("--urgency").arg(urgency.as_str());
+    }
+
+    if let Some(ref url) = notification.url {
+        cmd.arg("--open-url").arg(url);
+    }
+
+    for action in &notification.actions {
+        cmd.arg("--action").arg(&action.id).arg(&action.label);
+    }
+
     let output = cmd.output()?;
 
     if output.status.success() {
         info!("Notification shown successfully");
+        if let Some(id) = chosen_action(&String::from_utf8_lossy(&output.stdout)) {
+            run_action(notification, id)?;
+        }
         Ok(())
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -38,3 +108,66 @@ pub fn show(notification: &Notification) -> Result<()> {
         anyhow::bail!("Failed to show notification: {}", stderr)
     }
 }
+
+/// The action id the helper reported as chosen, if any
+fn chosen_action(stdout: &str) -> Option<&str> {
+    stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(ACTION_PREFIX))
+}
+
+/// Run the shell command mapped to the chosen action
+fn run_action(notification: &Notification, id: &str) -> Result<()> {
+    let Some(command) = notification.action_command(id) else {
+        info!("Action {} chosen (no command)", id);
+        return Ok(());
+    };
+
+    info!("Action {} chosen, running: {}", id, command);
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .status()
+        .with_context(|| format!("Failed to run command for action {}", id))?;
+    if !status.success() {
+        warn!("Command for action {} exited with {}", id, status);
+    }
+    Ok(())
+}
+
+/// Copy a sound file into ~/Library/Sounds and return the name to play it by.
+///
+/// macOS only plays notification sounds by name from the app bundle or the
+/// Library/Sounds directories, so an arbitrary path can't be referenced directly.
+fn register_sound_file(path: &Path) -> Result<String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !SUPPORTED_SOUND_EXTENSIONS.contains(&extension.as_str()) {
+        anyhow::bail!(
+            "unsupported sound format (expected one of: {})",
+            SUPPORTED_SOUND_EXTENSIONS.join(", ")
+        );
+    }
+
+    let file_name = path.file_name().context("sound file has no file name")?;
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("sound file name is not valid UTF-8")?;
+
+    let sounds_dir = dirs::home_dir()
+        .context("Could not find home directory")?
+        .join("Library")
+        .join("Sounds");
+    fs::create_dir_all(&sounds_dir)?;
+
+    let target = sounds_dir.join(file_name);
+    if fs::canonicalize(path)? != fs::canonicalize(&target).unwrap_or_default() {
+        fs::copy(path, &target).context("Failed to copy sound file")?;
+    }
+
+    Ok(name.to_string())
+}
@@ -7,8 +7,120 @@ mod linux;
 #[cfg(target_os = "windows")]
 mod windows;
 
+pub mod remote;
+
 use crate::client::message::Notification;
 use anyhow::Result;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Bundled icon used when a notification doesn't name one
+const DEFAULT_ICON: &str = "ahoy-icon-128";
+
+/// What a notification's `icon` refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Icon {
+    /// An image file, bundled or given by path
+    File(PathBuf),
+    /// A name for the platform's icon theme to look up
+    Themed(String),
+}
+
+/// Resolve a notification's icon, falling back to the bundled default icon in
+/// `icons_dir`. Anything that isn't an existing file is left for the
+/// platform's icon theme.
+pub fn resolve_icon(icon: Option<&str>, icons_dir: &Path) -> Icon {
+    match icon {
+        None => Icon::File(icons_dir.join(format!("{}.png", DEFAULT_ICON))),
+        Some(path) if Path::new(path).is_file() => Icon::File(PathBuf::from(path)),
+        Some(name) => Icon::Themed(name.to_string()),
+    }
+}
+
+/// Somewhere notifications can be shown
+pub trait NotificationBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()>;
+}
+
+/// How `ahoy send` shows notifications on this machine, from `local_backend`
+/// in config.toml
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalBackend {
+    /// The OS notifier
+    #[default]
+    Native,
+    /// Log notifications instead of showing them
+    Log,
+    /// Show nothing, e.g. when only forwarding to another device
+    None,
+}
+
+impl LocalBackend {
+    pub fn backend(self) -> Box<dyn NotificationBackend> {
+        match self {
+            LocalBackend::Native => native(),
+            LocalBackend::Log => Box::new(LogBackend),
+            LocalBackend::None => Box::new(NullBackend),
+        }
+    }
+}
+
+/// Drops every notification
+pub struct NullBackend;
+
+impl NotificationBackend for NullBackend {
+    fn deliver(&self, _notification: &Notification) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes notifications to the log instead of showing them
+pub struct LogBackend;
+
+impl NotificationBackend for LogBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()> {
+        info!(
+            "Notification: {}: {}",
+            notification.title, notification.body
+        );
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+struct UnsupportedBackend;
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+impl NotificationBackend for UnsupportedBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()> {
+        show(notification)
+    }
+}
+
+/// The OS notifier for this platform
+pub fn native() -> Box<dyn NotificationBackend> {
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(macos::MacosBackend)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(linux::LinuxBackend)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Box::new(windows::WindowsBackend)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    {
+        Box::new(UnsupportedBackend)
+    }
+}
 
 /// Show a native OS notification
 pub fn show(notification: &Notification) -> Result<()> {
@@ -32,3 +144,59 @@ pub fn show(notification: &Notification) -> Result<()> {
         anyhow::bail!("Notifications not supported on this platform")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/icons")
+    }
+
+    #[test]
+    fn test_no_icon_resolves_to_default_asset() {
+        let Icon::File(path) = resolve_icon(None, &bundled_dir()) else {
+            panic!("default icon did not resolve to a file");
+        };
+        assert!(path.is_file(), "missing {}", path.display());
+    }
+
+    #[test]
+    fn test_local_backend_from_config() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            local_backend: LocalBackend,
+        }
+
+        let parse = |s| toml::from_str::<Wrapper>(s).map(|w| w.local_backend);
+        assert_eq!(parse("local_backend = \"log\"").unwrap(), LocalBackend::Log);
+        assert_eq!(
+            parse("local_backend = \"none\"").unwrap(),
+            LocalBackend::None
+        );
+        assert!(parse("local_backend = \"carrier-pigeon\"").is_err());
+    }
+
+    #[test]
+    fn test_null_and_log_backends_succeed() {
+        let notification = Notification::new("Title", "Body");
+
+        assert!(LocalBackend::None.backend().deliver(&notification).is_ok());
+        assert!(LocalBackend::Log.backend().deliver(&notification).is_ok());
+    }
+
+    #[test]
+    fn test_icon_path_and_themed_name() {
+        let path = bundled_dir().join("ahoy-icon-128.png");
+        let path_str = path.to_str().unwrap();
+
+        assert_eq!(
+            resolve_icon(Some(path_str), &bundled_dir()),
+            Icon::File(path)
+        );
+        assert_eq!(
+            resolve_icon(Some("utilities-terminal"), &bundled_dir()),
+            Icon::Themed("utilities-terminal".to_string())
+        );
+    }
+}
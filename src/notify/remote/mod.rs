@@ -0,0 +1,306 @@
+mod ntfy;
+mod pushover;
+mod webhook;
+
+pub use pushover::Pushover;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+use ureq::tls::{RootCerts, TlsConfig, TlsProvider};
+
+use crate::client::message::{Notification, Urgency};
+use crate::config::Config;
+
+/// Give up on a remote endpoint after this long so a slow server can't hold up `ahoy send`
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where to forward notifications, from the `[forward]` section of config.toml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Forward {
+    /// Endpoint to POST to; for ntfy, the topic URL such as https://ntfy.sh/my-topic
+    pub url: String,
+    /// Payload format the endpoint expects
+    #[serde(default)]
+    pub kind: ForwardKind,
+    /// Sent as a bearer token in the Authorization header
+    pub token: Option<String>,
+    /// Only forward notifications with these urgencies; empty forwards all
+    #[serde(default)]
+    pub urgencies: Vec<Urgency>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardKind {
+    /// JSON object with title, body and priority
+    #[default]
+    Webhook,
+    /// ntfy's JSON publish format
+    Ntfy,
+}
+
+impl Forward {
+    /// Whether this notification's urgency is one the user wants forwarded
+    pub fn accepts(&self, notification: &Notification) -> bool {
+        let urgency = notification.urgency.unwrap_or_default();
+        self.urgencies.is_empty() || self.urgencies.contains(&urgency)
+    }
+}
+
+/// A configured remote destination for notifications
+#[derive(Debug, Clone, Copy)]
+pub enum Backend<'a> {
+    Forward(&'a Forward),
+    Pushover(&'a Pushover),
+}
+
+impl Backend<'_> {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Forward(forward) => match forward.kind {
+                ForwardKind::Webhook => "webhook",
+                ForwardKind::Ntfy => "ntfy",
+            },
+            Backend::Pushover(_) => "Pushover",
+        }
+    }
+
+    /// Deliver the notification to this backend
+    pub fn send(&self, notification: &Notification) -> Result<()> {
+        match self {
+            Backend::Forward(forward_config) => forward(notification, forward_config),
+            Backend::Pushover(pushover) => send_pushover(notification, pushover),
+        }
+    }
+}
+
+/// Remote backends that are configured, enabled and want this notification
+pub fn backends<'a>(config: &'a Config, notification: &Notification) -> Vec<Backend<'a>> {
+    let forward = config
+        .forward
+        .as_ref()
+        .filter(|f| f.accepts(notification))
+        .map(Backend::Forward);
+    let pushover = config
+        .pushover
+        .as_ref()
+        .filter(|p| p.enabled)
+        .map(Backend::Pushover);
+
+    forward.into_iter().chain(pushover).collect()
+}
+
+fn agent() -> ureq::Agent {
+    // Use the OS TLS stack and trust store rather than bundling our own
+    let tls = TlsConfig::builder()
+        .provider(TlsProvider::NativeTls)
+        .root_certs(RootCerts::PlatformVerifier)
+        .build();
+    ureq::Agent::config_builder()
+        .timeout_global(Some(REQUEST_TIMEOUT))
+        .tls_config(tls)
+        .build()
+        .into()
+}
+
+/// POST a notification to the configured endpoint
+pub fn forward(notification: &Notification, forward: &Forward) -> Result<()> {
+    info!("Forwarding notification to {}", forward.url);
+    let agent = agent();
+
+    let token = forward.token.as_deref();
+    match forward.kind {
+        ForwardKind::Webhook => {
+            post_json(&agent, &forward.url, token, &webhook::payload(notification))
+        }
+        ForwardKind::Ntfy => {
+            let (server, payload) = ntfy::payload(notification, &forward.url)?;
+            post_json(&agent, server, token, &payload)
+        }
+    }
+}
+
+/// Send a notification through the Pushover messages API
+pub fn send_pushover(notification: &Notification, pushover: &Pushover) -> Result<()> {
+    info!("Sending notification to Pushover");
+    agent()
+        .post(pushover::API_URL)
+        .send_form(pushover::form(notification, pushover))
+        .context("Failed to send notification to Pushover")?;
+    Ok(())
+}
+
+fn post_json(
+    agent: &ureq::Agent,
+    url: &str,
+    token: Option<&str>,
+    payload: &impl Serialize,
+) -> Result<()> {
+    let mut request = agent.post(url);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request
+        .send_json(payload)
+        .with_context(|| format!("Failed to POST notification to {}", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Accept one HTTP request on a local port, reply 200, and hand back its
+    /// headers and JSON body
+    fn mock_server() -> (String, thread::JoinHandle<(String, Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut headers = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                headers.push_str(&line);
+            }
+            let length: usize = headers
+                .lines()
+                .find_map(|l| {
+                    let (name, value) = l.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse().unwrap())
+                })
+                .unwrap();
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            (headers, serde_json::from_slice(&body).unwrap())
+        });
+
+        (url, handle)
+    }
+
+    fn endpoint(url: String, kind: ForwardKind) -> Forward {
+        Forward {
+            url,
+            kind,
+            token: None,
+            urgencies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_forward_webhook_posts_json() {
+        let (url, server) = mock_server();
+        let mut forward_config = endpoint(format!("{}/hook", url), ForwardKind::Webhook);
+        forward_config.token = Some("s3cret".to_string());
+        let notification =
+            Notification::new("Claude Code", "[app] Task finished").with_urgency(Urgency::Critical);
+
+        forward(&notification, &forward_config).unwrap();
+
+        let (headers, body) = server.join().unwrap();
+        assert!(headers.starts_with("POST /hook "));
+        assert!(headers.contains("Bearer s3cret"));
+        assert_eq!(body["title"], "Claude Code");
+        assert_eq!(body["body"], "[app] Task finished");
+        assert_eq!(body["priority"], "critical");
+    }
+
+    #[test]
+    fn test_forward_ntfy_posts_to_server_root() {
+        let (url, server) = mock_server();
+        let notification = Notification::new("Codex", "Task finished");
+
+        forward(
+            &notification,
+            &endpoint(format!("{}/agents", url), ForwardKind::Ntfy),
+        )
+        .unwrap();
+
+        let (headers, body) = server.join().unwrap();
+        assert!(headers.starts_with("POST / "));
+        assert_eq!(body["topic"], "agents");
+        assert_eq!(body["title"], "Codex");
+        assert_eq!(body["message"], "Task finished");
+        assert_eq!(body["priority"], 3);
+    }
+
+    #[test]
+    fn test_forward_unreachable_endpoint_errors() {
+        // Bind then drop a listener to get a port nothing is listening on
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let forward_config = endpoint(format!("http://127.0.0.1:{}", port), ForwardKind::Webhook);
+
+        assert!(forward(&Notification::new("T", "B"), &forward_config).is_err());
+    }
+
+    #[test]
+    fn test_accepts_filters_by_urgency() {
+        let mut forward_config = endpoint("https://example.com".to_string(), ForwardKind::Webhook);
+        let normal = Notification::new("T", "B");
+        let critical = Notification::new("T", "B").with_urgency(Urgency::Critical);
+
+        assert!(forward_config.accepts(&normal));
+
+        forward_config.urgencies = vec![Urgency::Critical];
+        assert!(!forward_config.accepts(&normal));
+        assert!(forward_config.accepts(&critical));
+    }
+
+    #[test]
+    fn test_backends_only_configured_and_enabled() {
+        let notification = Notification::new("T", "B");
+        let mut config = Config::default();
+        assert!(backends(&config, &notification).is_empty());
+
+        config.forward = Some(endpoint(
+            "https://example.com".to_string(),
+            ForwardKind::Ntfy,
+        ));
+        config.pushover = Some(Pushover {
+            token: "t".to_string(),
+            user: "u".to_string(),
+            enabled: false,
+        });
+        let names: Vec<&str> = backends(&config, &notification)
+            .iter()
+            .map(Backend::name)
+            .collect();
+        assert_eq!(names, ["ntfy"]);
+
+        config.pushover.as_mut().unwrap().enabled = true;
+        assert_eq!(backends(&config, &notification).len(), 2);
+    }
+
+    #[test]
+    fn test_deserialize_forward_section() {
+        let forward_config: Forward = toml::from_str(
+            "url = \"https://ntfy.sh/mine\"\nkind = \"ntfy\"\ntoken = \"tk\"\nurgencies = [\"critical\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(forward_config.kind, ForwardKind::Ntfy);
+        assert_eq!(forward_config.token.as_deref(), Some("tk"));
+        assert_eq!(forward_config.urgencies, vec![Urgency::Critical]);
+    }
+}
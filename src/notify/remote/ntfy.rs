@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::borrow::Cow;
+
+use crate::client::message::{Notification, Urgency};
+
+/// Body for ntfy's JSON publish API, which is POSTed to the server root
+#[derive(Debug, Serialize)]
+pub struct Payload<'a> {
+    pub topic: &'a str,
+    pub title: &'a str,
+    /// The body, below the subtitle if there is one
+    pub message: Cow<'a, str>,
+    /// 1 (min) to 5 (max)
+    pub priority: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click: Option<&'a str>,
+}
+
+fn priority(urgency: Urgency) -> u8 {
+    match urgency {
+        Urgency::Low => 2,
+        Urgency::Normal => 3,
+        Urgency::Critical => 5,
+    }
+}
+
+/// Split a topic URL into the server to POST to and the JSON body
+pub fn payload<'a>(
+    notification: &'a Notification,
+    topic_url: &'a str,
+) -> Result<(&'a str, Payload<'a>)> {
+    let (server, topic) = topic_url
+        .trim_end_matches('/')
+        .rsplit_once('/')
+        .filter(|(server, topic)| {
+            server.contains("://") && !server.ends_with('/') && !topic.is_empty()
+        })
+        .with_context(|| {
+            format!(
+                "ntfy url '{}' should be a topic URL like https://ntfy.sh/my-topic",
+                topic_url
+            )
+        })?;
+
+    Ok((
+        server,
+        Payload {
+            topic,
+            title: &notification.title,
+            message: notification.body_with_subtitle(),
+            priority: priority(notification.urgency.unwrap_or_default()),
+            click: notification.url.as_deref(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_splits_topic_url() {
+        let notification = Notification::new("Codex", "Task finished");
+
+        let (server, payload) = payload(&notification, "https://ntfy.sh/my-agents/").unwrap();
+
+        assert_eq!(server, "https://ntfy.sh");
+        assert_eq!(payload.topic, "my-agents");
+        assert_eq!(payload.message, "Task finished");
+        assert_eq!(payload.priority, 3);
+    }
+
+    #[test]
+    fn test_payload_puts_subtitle_above_message() {
+        let notification = Notification::new("Codex", "Task finished").with_subtitle("myapp");
+
+        let (_, payload) = payload(&notification, "https://ntfy.sh/my-agents").unwrap();
+
+        assert_eq!(payload.message, "myapp\nTask finished");
+    }
+
+    #[test]
+    fn test_payload_rejects_url_without_topic() {
+        let notification = Notification::new("Codex", "Task finished");
+
+        assert!(payload(&notification, "https://ntfy.sh").is_err());
+        assert!(payload(&notification, "https://ntfy.sh/").is_err());
+    }
+
+    #[test]
+    fn test_priority_mapping() {
+        assert_eq!(priority(Urgency::Low), 2);
+        assert_eq!(priority(Urgency::Normal), 3);
+        assert_eq!(priority(Urgency::Critical), 5);
+    }
+}
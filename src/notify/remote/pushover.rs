@@ -0,0 +1,120 @@
+use serde::Deserialize;
+
+use crate::client::message::{Notification, Urgency};
+
+pub const API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// Pushover credentials, from the `[pushover]` section of config.toml
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pushover {
+    /// Application API token
+    pub token: String,
+    /// User or group key to deliver to
+    pub user: String,
+    /// Set to false to keep the keys in config but stop sending
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Pushover priority on its -2..2 scale. Critical maps to 1 (high) rather than
+/// 2, since emergency priority needs retry/expire settings and an acknowledgement.
+fn priority(urgency: Urgency) -> i8 {
+    match urgency {
+        Urgency::Low => -1,
+        Urgency::Normal => 0,
+        Urgency::Critical => 1,
+    }
+}
+
+/// Form fields for the messages API
+pub fn form<'a>(
+    notification: &'a Notification,
+    pushover: &'a Pushover,
+) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("token", pushover.token.clone()),
+        ("user", pushover.user.clone()),
+        ("title", notification.title.clone()),
+        ("message", notification.body_with_subtitle().into_owned()),
+        (
+            "priority",
+            priority(notification.urgency.unwrap_or_default()).to_string(),
+        ),
+    ];
+    if let Some(ref url) = notification.url {
+        fields.push(("url", url.clone()));
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Pushover {
+        Pushover {
+            token: "app-token".to_string(),
+            user: "user-key".to_string(),
+            enabled: true,
+        }
+    }
+
+    fn field<'a>(fields: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+        fields
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_form_fields() {
+        let notification = Notification::new("Claude Code", "[app] Task finished")
+            .with_url("https://example.com/run/7");
+
+        let fields = form(&notification, &keys());
+
+        assert_eq!(field(&fields, "token"), Some("app-token"));
+        assert_eq!(field(&fields, "user"), Some("user-key"));
+        assert_eq!(field(&fields, "title"), Some("Claude Code"));
+        assert_eq!(field(&fields, "message"), Some("[app] Task finished"));
+        assert_eq!(field(&fields, "priority"), Some("0"));
+        assert_eq!(field(&fields, "url"), Some("https://example.com/run/7"));
+    }
+
+    #[test]
+    fn test_form_message_includes_subtitle() {
+        let notification = Notification::new("Claude Code", "Task finished").with_subtitle("myapp");
+
+        let fields = form(&notification, &keys());
+
+        assert_eq!(field(&fields, "message"), Some("myapp\nTask finished"));
+    }
+
+    #[test]
+    fn test_form_priority_mapping() {
+        let pushover = keys();
+        let priority_for = |urgency| {
+            let notification = Notification::new("T", "B").with_urgency(urgency);
+            field(&form(&notification, &pushover), "priority").map(str::to_string)
+        };
+
+        assert_eq!(priority_for(Urgency::Low).as_deref(), Some("-1"));
+        assert_eq!(priority_for(Urgency::Normal).as_deref(), Some("0"));
+        assert_eq!(priority_for(Urgency::Critical).as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_deserialize_enabled_by_default() {
+        let pushover: Pushover = toml::from_str("token = \"t\"\nuser = \"u\"\n").unwrap();
+        assert!(pushover.enabled);
+
+        let pushover: Pushover =
+            toml::from_str("token = \"t\"\nuser = \"u\"\nenabled = false\n").unwrap();
+        assert!(!pushover.enabled);
+    }
+}
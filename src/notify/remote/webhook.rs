@@ -0,0 +1,67 @@
+use serde::Serialize;
+
+use crate::client::message::Notification;
+
+/// JSON body POSTed to a generic webhook
+#[derive(Debug, Serialize)]
+pub struct Payload<'a> {
+    pub title: &'a str,
+    pub body: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<&'a str>,
+    /// "low", "normal" or "critical"
+    pub priority: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<&'a str>,
+}
+
+pub fn payload(notification: &Notification) -> Payload<'_> {
+    Payload {
+        title: &notification.title,
+        body: &notification.body,
+        subtitle: notification.subtitle.as_deref(),
+        priority: notification.urgency.unwrap_or_default().as_str(),
+        url: notification.url.as_deref(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::message::Urgency;
+
+    #[test]
+    fn test_payload_defaults_to_normal_priority() {
+        let notification = Notification::new("Claude Code", "Task finished");
+
+        let json = serde_json::to_value(payload(&notification)).unwrap();
+
+        assert_eq!(json["title"], "Claude Code");
+        assert_eq!(json["body"], "Task finished");
+        assert_eq!(json["priority"], "normal");
+        assert!(json.get("url").is_none());
+        assert!(json.get("subtitle").is_none());
+    }
+
+    #[test]
+    fn test_payload_includes_subtitle() {
+        let notification = Notification::new("Claude Code", "Task finished").with_subtitle("myapp");
+
+        let json = serde_json::to_value(payload(&notification)).unwrap();
+
+        assert_eq!(json["subtitle"], "myapp");
+        assert_eq!(json["body"], "Task finished");
+    }
+
+    #[test]
+    fn test_payload_includes_urgency_and_url() {
+        let notification = Notification::new("CI", "Build failed")
+            .with_urgency(Urgency::Critical)
+            .with_url("https://ci.example.com/42");
+
+        let json = serde_json::to_value(payload(&notification)).unwrap();
+
+        assert_eq!(json["priority"], "critical");
+        assert_eq!(json["url"], "https://ci.example.com/42");
+    }
+}
@@ -1,6 +1,16 @@
 use anyhow::Result;
 
 use crate::client::message::Notification;
+use crate::notify::NotificationBackend;
+
+/// Shows notifications through toast notifications
+pub struct WindowsBackend;
+
+impl NotificationBackend for WindowsBackend {
+    fn deliver(&self, notification: &Notification) -> Result<()> {
+        show(notification)
+    }
+}
 
 pub fn show(_notification: &Notification) -> Result<()> {
     anyhow::bail!("Windows notifications not yet implemented")
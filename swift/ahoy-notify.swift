@@ -43,21 +43,43 @@ fputs("Bundle.main.bundleIdentifier = \(Bundle.main.bundleIdentifier ?? "nil")\n
 
 // MARK: - Notification Delegate for handling clicks
 class NotificationDelegate: NSObject, NSUserNotificationCenterDelegate {
-    var activateBundleId: String?
+    var activateBundleIds: [String] = []
+    var openURL: URL?
+    var actionIds: [String] = []
     var didActivate = false
 
     func userNotificationCenter(_ center: NSUserNotificationCenter, didActivate notification: NSUserNotification) {
         didActivate = true
-        if let bundleId = activateBundleId {
-            let runningApps = NSWorkspace.shared.runningApplications.filter { $0.bundleIdentifier == bundleId }
-            if let app = runningApps.first {
+
+        // Report a chosen action on stdout; ahoy runs the mapped command
+        var chosenAction: String? = nil
+        if notification.activationType == .additionalActionClicked {
+            chosenAction = notification.additionalActivationAction?.identifier
+        } else if notification.activationType == .actionButtonClicked {
+            chosenAction = actionIds.first
+        }
+        if let id = chosenAction {
+            print("action:\(id)")
+            fflush(stdout)
+            exit(0)
+        }
+
+        if let url = openURL {
+            // A link takes precedence over activating an app
+            NSWorkspace.shared.open(url)
+        } else if !activateBundleIds.isEmpty {
+            // Activate the first target that's running, in the order given
+            let running = activateBundleIds.lazy.compactMap { bundleId in
+                NSWorkspace.shared.runningApplications.first { $0.bundleIdentifier == bundleId }
+            }.first
+            if let app = running {
                 // Activate existing instance - use activate() without deprecated options
                 app.activate()
             } else {
-                // App not running - use open command which is more reliable
+                // Nothing running - use open command which is more reliable
                 let task = Process()
                 task.launchPath = "/usr/bin/open"
-                task.arguments = ["-b", bundleId]
+                task.arguments = ["-b", activateBundleIds[0]]
                 try? task.run()
             }
         }
@@ -77,7 +99,7 @@ app.setActivationPolicy(.accessory)
 
 let args = CommandLine.arguments
 guard args.count >= 3 else {
-    fputs("Usage: ahoy-notify <title> <body> [--sound <name>] [--activate <bundle-id>]\n", stderr)
+    fputs("Usage: ahoy-notify <title> <body> [--sound <name>] [--icon <path>] [--activate <bundle-id>]... [--open-url <url>] [--urgency <level>] [--action <id> <label>]... [--replace-id <key>] [--subtitle <text>]\n", stderr)
     exit(1)
 }
 
@@ -86,7 +108,13 @@ let body = args[2]
 
 var soundName = "Glass"
 var iconPath: String? = nil
-var activateBundleId: String? = nil
+var contentImagePath: String? = nil
+var activateBundleIds: [String] = []
+var openURL: URL? = nil
+var urgency = "normal"
+var actions: [(id: String, label: String)] = []
+var replaceId: String? = nil
+var subtitle: String? = nil
 
 // Default icon path - check Resources directory (for app bundle) then same directory as binary
 // Prefer 512px icon for Retina displays, fallback to 128px
@@ -114,25 +142,47 @@ while i < args.count {
         i += 2
     } else if args[i] == "--icon" && i + 1 < args.count {
         iconPath = args[i + 1]
+        contentImagePath = args[i + 1]
         i += 2
     } else if args[i] == "--activate" && i + 1 < args.count {
-        activateBundleId = args[i + 1]
+        activateBundleIds.append(args[i + 1])
+        i += 2
+    } else if args[i] == "--action" && i + 2 < args.count {
+        actions.append((id: args[i + 1], label: args[i + 2]))
+        i += 3
+    } else if args[i] == "--subtitle" && i + 1 < args.count {
+        subtitle = args[i + 1]
+        i += 2
+    } else if args[i] == "--replace-id" && i + 1 < args.count {
+        replaceId = args[i + 1]
+        i += 2
+    } else if args[i] == "--urgency" && i + 1 < args.count {
+        urgency = args[i + 1]
+        i += 2
+    } else if args[i] == "--open-url" && i + 1 < args.count {
+        openURL = URL(string: args[i + 1])
+        if openURL == nil {
+            fputs("Invalid URL: \(args[i + 1])\n", stderr)
+        }
         i += 2
     } else {
         i += 1
     }
 }
 
-notificationDelegate.activateBundleId = activateBundleId
+notificationDelegate.activateBundleIds = activateBundleIds
+notificationDelegate.openURL = openURL
+notificationDelegate.actionIds = actions.map { $0.id }
 NSUserNotificationCenter.default.delegate = notificationDelegate
 
 // MARK: - Focus Check
 // If the source terminal is focused, user is already watching - don't notify.
-if let bundleId = activateBundleId {
+// Critical notifications are always shown.
+if !activateBundleIds.isEmpty && urgency != "critical" {
     let frontmostApp = NSWorkspace.shared.frontmostApplication
     let frontmostBundleId = frontmostApp?.bundleIdentifier
 
-    if frontmostBundleId == bundleId {
+    if let bundleId = frontmostBundleId, activateBundleIds.contains(bundleId) {
         fputs("Terminal is focused (\(bundleId)), skipping notification\n", stderr)
         exit(0)
     }
@@ -140,19 +190,50 @@ if let bundleId = activateBundleId {
 }
 
 let notification = NSUserNotification()
+// Delivering with the identifier of one already shown replaces it in place
+if let key = replaceId {
+    notification.identifier = "ahoy.replace.\(key)"
+}
 notification.title = title
+notification.subtitle = subtitle
 notification.informativeText = body
-notification.soundName = soundName
+// "none" means silent; unknown names fall back to the default sound
+if soundName.lowercased() == "none" {
+    notification.soundName = nil
+} else if NSSound(named: NSSound.Name(soundName)) != nil {
+    notification.soundName = soundName
+} else {
+    fputs("Unknown sound \(soundName), using Glass\n", stderr)
+    notification.soundName = "Glass"
+}
 
-// The left side now shows the app icon via bundle swizzling
-// No need to set contentImage (right side) anymore
+// The first action is the main button; with several, all appear in its dropdown
+if let first = actions.first {
+    notification.hasActionButton = true
+    notification.actionButtonTitle = first.label
+    if actions.count > 1 {
+        notification.additionalActions = actions.map {
+            NSUserNotificationAction(identifier: $0.id, title: $0.label)
+        }
+    }
+}
+
+// The left side shows the app icon via bundle swizzling; an icon passed
+// with --icon (e.g. the agent's) is attached on the right
+if let path = contentImagePath {
+    if let image = NSImage(contentsOfFile: path) {
+        notification.contentImage = image
+    } else {
+        fputs("Could not load icon \(path)\n", stderr)
+    }
+}
 
 NSUserNotificationCenter.default.deliver(notification)
 fputs("Notification delivered\n", stderr)
 
-// If we have an activation target, wait for user to click
+// If we have an activation target, link or actions, wait for user to click
 // Otherwise just keep the process alive briefly so notification can be delivered
-if activateBundleId != nil {
+if !activateBundleIds.isEmpty || openURL != nil || !actions.isEmpty {
     let timeout = Date(timeIntervalSinceNow: 60)
     while !notificationDelegate.didActivate && Date() < timeout {
         RunLoop.current.run(until: Date(timeIntervalSinceNow: 0.1))
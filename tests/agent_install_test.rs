@@ -0,0 +1,256 @@
+mod common;
+
+use ahoy::install::setup::{self, InstallOptions};
+use ahoy::install::uninstall;
+use common::{TestHome, agents};
+use serial_test::serial;
+use std::fs;
+
+#[test]
+#[serial]
+fn test_all_agents_install_creates_config() {
+    for agent in agents() {
+        let home = TestHome::new();
+
+        (agent.install)().unwrap();
+
+        assert!(home.config_file(&agent).exists(), "{}", agent.name);
+        assert!((agent.is_installed)(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_all_agents_install_idempotent() {
+    for agent in agents() {
+        let home = TestHome::new();
+
+        (agent.install)().unwrap();
+        let first = home.read_config(&agent);
+        (agent.install)().unwrap();
+        let second = home.read_config(&agent);
+
+        assert_eq!(first, second, "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_all_agents_not_installed_without_config() {
+    for agent in agents() {
+        let _home = TestHome::new();
+
+        assert!(!(agent.is_installed)(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_all_agents_uninstall_removes_hooks() {
+    for agent in agents() {
+        let home = TestHome::new();
+
+        (agent.install)().unwrap();
+        (agent.uninstall)().unwrap();
+
+        assert!(!(agent.is_installed)(), "{}", agent.name);
+        assert!(
+            !home.read_config(&agent).contains("ahoy send"),
+            "{}",
+            agent.name
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn test_all_agents_uninstall_without_config() {
+    for agent in agents() {
+        let _home = TestHome::new();
+
+        (agent.uninstall)().unwrap();
+
+        assert!(!(agent.is_installed)(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_all_agents_preserve_foreign_hooks() {
+    for agent in agents() {
+        let home = TestHome::new();
+        home.write_config(&agent, agent.foreign_config);
+
+        (agent.install)().unwrap();
+        assert!(
+            home.read_config(&agent).contains(agent.foreign_marker),
+            "{}",
+            agent.name
+        );
+
+        (agent.uninstall)().unwrap();
+        assert!(
+            home.read_config(&agent).contains(agent.foreign_marker),
+            "{}",
+            agent.name
+        );
+    }
+}
+
+fn install_all() {
+    setup::run(Some("all".to_string()), InstallOptions::default()).unwrap();
+}
+
+#[test]
+#[serial]
+fn test_detect_agents_by_config_dir() {
+    let home = TestHome::new();
+    let path = home.dir.path();
+
+    assert!(setup::detect_agents(path).is_empty());
+
+    fs::create_dir(path.join(".codex")).unwrap();
+    fs::create_dir(path.join(".gemini")).unwrap();
+    assert_eq!(setup::detect_agents(path), ["Codex", "Gemini CLI"]);
+
+    fs::create_dir(path.join(".claude")).unwrap();
+    fs::create_dir(path.join(".cursor")).unwrap();
+    assert_eq!(
+        setup::detect_agents(path),
+        ["Claude Code", "Codex", "Gemini CLI", "Cursor"]
+    );
+}
+
+#[test]
+#[serial]
+fn test_install_all_without_agents_is_noop() {
+    let home = TestHome::new();
+
+    install_all();
+
+    for agent in agents() {
+        assert!(!home.config_file(&agent).exists(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_install_all_only_installs_present_agents() {
+    let present = ["codex", "gemini"];
+    let home = TestHome::new();
+    for agent in agents().iter().filter(|a| present.contains(&a.name)) {
+        fs::create_dir(home.dir.path().join(format!(".{}", agent.name))).unwrap();
+    }
+
+    install_all();
+
+    for agent in agents() {
+        assert_eq!(
+            (agent.is_installed)(),
+            present.contains(&agent.name),
+            "{}",
+            agent.name
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn test_install_all_installs_every_present_agent() {
+    let home = TestHome::new();
+    for agent in agents() {
+        fs::create_dir(home.dir.path().join(format!(".{}", agent.name))).unwrap();
+    }
+
+    install_all();
+
+    for agent in agents() {
+        assert!((agent.is_installed)(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_install_all_continues_past_a_failing_agent() {
+    let home = TestHome::new();
+    for agent in agents() {
+        fs::create_dir(home.dir.path().join(format!(".{}", agent.name))).unwrap();
+    }
+    let codex = agents().into_iter().find(|a| a.name == "codex").unwrap();
+    home.write_config(&codex, "model = \n");
+
+    let result = setup::run(Some("all".to_string()), InstallOptions::default());
+
+    assert!(result.is_err());
+    for agent in agents() {
+        assert_eq!(
+            (agent.is_installed)(),
+            agent.name != "codex",
+            "{}",
+            agent.name
+        );
+    }
+}
+
+/// Install every agent's hooks and write some ahoy data alongside an unrelated file
+fn setup_purge(home: &TestHome) {
+    for agent in agents() {
+        (agent.install)().unwrap();
+    }
+    let path = home.dir.path();
+    fs::create_dir_all(path.join(".ahoy/bin")).unwrap();
+    fs::write(path.join(".ahoy/history.jsonl"), "{}\n").unwrap();
+    fs::write(path.join(".ahoy/bin/ahoy"), "").unwrap();
+    fs::create_dir_all(path.join(".config/other")).unwrap();
+    fs::write(path.join(".config/other/keep.toml"), "").unwrap();
+}
+
+#[test]
+#[serial]
+fn test_purge_declined_removes_nothing() {
+    let home = TestHome::new();
+    setup_purge(&home);
+
+    uninstall::purge(false, &mut "n\n".as_bytes()).unwrap();
+
+    assert!(home.dir.path().join(".ahoy/history.jsonl").exists());
+    for agent in agents() {
+        assert!((agent.is_installed)(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_purge_confirmed_removes_hooks_and_ahoy_files() {
+    let home = TestHome::new();
+    setup_purge(&home);
+    if cfg!(target_os = "linux") {
+        fs::create_dir_all(home.dir.path().join(".config/ahoy")).unwrap();
+        fs::write(home.dir.path().join(".config/ahoy/config.toml"), "").unwrap();
+    }
+
+    uninstall::purge(false, &mut "y\n".as_bytes()).unwrap();
+
+    assert!(!home.dir.path().join(".ahoy").exists());
+    assert!(!home.dir.path().join(".config/ahoy").exists());
+    assert!(home.dir.path().join(".config/other/keep.toml").exists());
+    for agent in agents() {
+        assert!(!(agent.is_installed)(), "{}", agent.name);
+        assert!(home.config_file(&agent).exists(), "{}", agent.name);
+    }
+}
+
+#[test]
+#[serial]
+fn test_purge_yes_skips_prompt() {
+    let home = TestHome::new();
+    setup_purge(&home);
+
+    // Nothing to read: --yes must not wait for an answer
+    uninstall::purge(true, &mut "".as_bytes()).unwrap();
+
+    assert!(!home.dir.path().join(".ahoy").exists());
+    for agent in agents() {
+        assert!(!(agent.is_installed)(), "{}", agent.name);
+    }
+}
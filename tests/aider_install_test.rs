@@ -0,0 +1,149 @@
+use ahoy::install::aider;
+use ahoy::install::setup::InstallOptions;
+use serial_test::serial;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+// Helper to set up a test home directory
+fn setup_test_env() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    unsafe {
+        std::env::set_var("AHOY_TEST_HOME", temp_dir.path());
+    }
+    temp_dir
+}
+
+fn wrapper_file(temp_dir: &TempDir) -> PathBuf {
+    temp_dir.path().join(".ahoy/bin/aider")
+}
+
+#[test]
+#[serial]
+fn test_install_writes_wrapper() {
+    let temp_dir = setup_test_env();
+
+    aider::install().unwrap();
+
+    let content = fs::read_to_string(wrapper_file(&temp_dir)).unwrap();
+    assert!(content.starts_with("#!/bin/sh"));
+    assert!(content.contains("--agent aider"));
+    assert!(aider::is_installed());
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_install_makes_wrapper_executable() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_dir = setup_test_env();
+
+    aider::install().unwrap();
+
+    let mode = fs::metadata(wrapper_file(&temp_dir))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o111, 0o111);
+}
+
+#[test]
+#[serial]
+fn test_install_idempotent() {
+    let temp_dir = setup_test_env();
+
+    aider::install().unwrap();
+    let first = fs::read_to_string(wrapper_file(&temp_dir)).unwrap();
+    aider::install().unwrap();
+    let second = fs::read_to_string(wrapper_file(&temp_dir)).unwrap();
+
+    assert_eq!(first, second);
+}
+
+#[test]
+#[serial]
+fn test_install_refuses_to_overwrite_foreign_file() {
+    let temp_dir = setup_test_env();
+    let wrapper = wrapper_file(&temp_dir);
+    fs::create_dir_all(wrapper.parent().unwrap()).unwrap();
+    fs::write(&wrapper, "#!/bin/sh\nexec my-aider \"$@\"\n").unwrap();
+
+    assert!(
+        aider::install_with(&InstallOptions {
+            replace: true,
+            ..Default::default()
+        })
+        .is_err()
+    );
+    assert!(!aider::is_installed());
+    assert!(fs::read_to_string(&wrapper).unwrap().contains("my-aider"));
+}
+
+#[test]
+#[serial]
+fn test_uninstall_removes_wrapper() {
+    let temp_dir = setup_test_env();
+
+    aider::install().unwrap();
+    aider::uninstall().unwrap();
+
+    assert!(!wrapper_file(&temp_dir).exists());
+    assert!(!aider::is_installed());
+}
+
+#[test]
+#[serial]
+fn test_uninstall_leaves_foreign_file() {
+    let temp_dir = setup_test_env();
+    let wrapper = wrapper_file(&temp_dir);
+    fs::create_dir_all(wrapper.parent().unwrap()).unwrap();
+    fs::write(&wrapper, "#!/bin/sh\n").unwrap();
+
+    aider::uninstall().unwrap();
+
+    assert!(wrapper.exists());
+}
+
+#[test]
+#[serial]
+fn test_uninstall_without_wrapper() {
+    let _temp_dir = setup_test_env();
+
+    aider::uninstall().unwrap();
+
+    assert!(!aider::is_installed());
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_wrapper_skips_itself_when_path_spells_its_dir_differently() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_dir = setup_test_env();
+    aider::install().unwrap();
+
+    let real_dir = temp_dir.path().join("real");
+    fs::create_dir_all(&real_dir).unwrap();
+    let real_aider = real_dir.join("aider");
+    fs::write(&real_aider, "#!/bin/sh\necho \"real aider $1\"\nexit 3\n").unwrap();
+    fs::set_permissions(&real_aider, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let wrapper_dir = temp_dir.path().join(".ahoy/bin");
+    let path = format!(
+        "{}/:{}/.:{}:/usr/bin:/bin",
+        wrapper_dir.display(),
+        wrapper_dir.display(),
+        real_dir.display()
+    );
+    let output = std::process::Command::new(wrapper_file(&temp_dir))
+        .arg("--version")
+        .env("PATH", path)
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "real aider --version\n"
+    );
+}
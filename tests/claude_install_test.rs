@@ -1,4 +1,6 @@
 use ahoy::install::claude;
+use ahoy::install::settings::SettingsFormat;
+use ahoy::install::setup::InstallOptions;
 use serde_json::{Value, json};
 use serial_test::serial;
 use std::fs;
@@ -236,3 +238,182 @@ fn test_install_creates_parent_directory() {
     assert!(temp_dir.path().join(".claude").exists());
     assert!(temp_dir.path().join(".claude/settings.json").exists());
 }
+
+#[test]
+#[serial]
+fn test_install_preserves_minified_settings() {
+    let temp_dir = setup_test_env();
+    let claude_dir = temp_dir.path().join(".claude");
+    fs::create_dir_all(&claude_dir).unwrap();
+    fs::write(claude_dir.join("settings.json"), r#"{"model":"opus"}"#).unwrap();
+
+    claude::install().unwrap();
+
+    let content = fs::read_to_string(claude_dir.join("settings.json")).unwrap();
+    assert!(!content.contains('\n'));
+    assert!(content.contains("ahoy"));
+}
+
+#[test]
+#[serial]
+fn test_install_with_compact_format() {
+    let temp_dir = setup_test_env();
+    write_settings(&temp_dir, json!({"model": "opus"}));
+
+    claude::install_with(&InstallOptions {
+        settings_format: SettingsFormat::Compact,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let content = fs::read_to_string(temp_dir.path().join(".claude/settings.json")).unwrap();
+    assert!(!content.contains('\n'));
+    assert_eq!(read_settings(&temp_dir)["model"], "opus");
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_install_writes_through_symlinked_settings() {
+    let temp_dir = setup_test_env();
+    let dotfiles = temp_dir.path().join("dotfiles");
+    fs::create_dir_all(&dotfiles).unwrap();
+    let real_settings = dotfiles.join("claude-settings.json");
+    fs::write(&real_settings, r#"{"model": "opus"}"#).unwrap();
+
+    let claude_dir = temp_dir.path().join(".claude");
+    fs::create_dir_all(&claude_dir).unwrap();
+    let link = claude_dir.join("settings.json");
+    std::os::unix::fs::symlink(&real_settings, &link).unwrap();
+
+    claude::install().unwrap();
+
+    // The link is left in place and the real file receives the hooks
+    assert!(
+        fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+    let settings: Value =
+        serde_json::from_str(&fs::read_to_string(&real_settings).unwrap()).unwrap();
+    assert_eq!(settings["model"], "opus");
+    assert!(claude::is_installed());
+
+    claude::uninstall().unwrap();
+
+    assert!(
+        fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+    assert!(!claude::is_installed());
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_install_dangling_symlink_errors() {
+    let temp_dir = setup_test_env();
+    let claude_dir = temp_dir.path().join(".claude");
+    fs::create_dir_all(&claude_dir).unwrap();
+    std::os::unix::fs::symlink(
+        temp_dir.path().join("missing.json"),
+        claude_dir.join("settings.json"),
+    )
+    .unwrap();
+
+    let err = claude::install().unwrap_err();
+    assert!(err.to_string().contains("symlink"));
+}
+
+#[test]
+#[serial]
+fn test_install_replace_upgrades_old_hooks() {
+    let temp_dir = setup_test_env();
+    write_settings(
+        &temp_dir,
+        json!({
+            "hooks": {
+                "Stop": [
+                    {
+                        "matcher": "",
+                        "hooks": [{
+                            "type": "command",
+                            "command": "/other/tool --flag",
+                            "timeout": 5000
+                        }]
+                    },
+                    {
+                        "matcher": "",
+                        "hooks": [{
+                            "type": "command",
+                            "command": "~/.ahoy/bin/ahoy send --from-claude -t 'Claude Code'",
+                            "timeout": 5000
+                        }]
+                    }
+                ],
+                "Notification": [
+                    {
+                        "matcher": "idle_prompt",
+                        "hooks": [{
+                            "type": "command",
+                            "command": "~/.ahoy/bin/ahoy send -t 'Claude Code' 'Waiting'",
+                            "timeout": 5000
+                        }]
+                    }
+                ]
+            }
+        }),
+    );
+
+    claude::install_with(&InstallOptions {
+        replace: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let settings = read_settings(&temp_dir);
+    let stop_hooks = settings["hooks"]["Stop"].as_array().unwrap();
+    assert_eq!(stop_hooks.len(), 2);
+    assert_eq!(stop_hooks[0]["hooks"][0]["command"], "/other/tool --flag");
+    let command = stop_hooks[1]["hooks"][0]["command"].as_str().unwrap();
+    assert!(command.contains("--agent claude"));
+
+    let notification_hooks = settings["hooks"]["Notification"].as_array().unwrap();
+    assert_eq!(notification_hooks.len(), 2);
+    for hook in notification_hooks {
+        let command = hook["hooks"][0]["command"].as_str().unwrap();
+        assert!(command.contains("--agent claude"));
+    }
+}
+
+#[test]
+#[serial]
+fn test_install_without_replace_keeps_old_hooks() {
+    let temp_dir = setup_test_env();
+    write_settings(
+        &temp_dir,
+        json!({
+            "hooks": {
+                "Stop": [{
+                    "matcher": "",
+                    "hooks": [{
+                        "type": "command",
+                        "command": "~/.ahoy/bin/ahoy send --from-claude -t 'Claude Code'",
+                        "timeout": 5000
+                    }]
+                }]
+            }
+        }),
+    );
+
+    claude::install().unwrap();
+
+    let settings = read_settings(&temp_dir);
+    let command = settings["hooks"]["Stop"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap();
+    assert!(command.contains("-t 'Claude Code'"));
+}
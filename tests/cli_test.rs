@@ -0,0 +1,253 @@
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Output};
+
+fn ahoy(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ahoy"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stderr_json(output: &Output) -> Value {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().last().unwrap();
+    serde_json::from_str(line).unwrap()
+}
+
+#[test]
+fn test_json_errors_for_runtime_error() {
+    let output = ahoy(&["--json-errors", "send", "--json", "not json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let error = stderr_json(&output);
+    assert_eq!(error["code"], 1);
+    assert!(
+        error["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Failed to parse notification JSON: ")
+    );
+}
+
+#[test]
+fn test_json_errors_for_usage_error() {
+    let output = ahoy(&["send", "--json-errors", "--urgency", "loud", "hi"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    let error = stderr_json(&output);
+    assert_eq!(error["code"], 2);
+    assert!(error["error"].as_str().unwrap().contains("'loud'"));
+}
+
+#[test]
+fn test_errors_are_text_by_default() {
+    let output = ahoy(&["send", "--json", "not json"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("Error: Failed to parse notification JSON"));
+}
+
+#[test]
+fn test_json_errors_flag_as_a_message_is_not_a_flag() {
+    let output = ahoy(&["send", "--json", "not json", "--", "--json-errors"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("Error: Failed to parse notification JSON"));
+}
+
+#[test]
+fn test_completions_bash() {
+    let output = ahoy(&["completions", "bash"]);
+
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    assert!(script.contains("ahoy"));
+    assert!(script.contains("send"));
+    assert!(script.contains("install"));
+}
+
+#[test]
+fn test_completions_unknown_shell() {
+    let output = ahoy(&["completions", "tcsh"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_hook_formats_are_exclusive() {
+    let output = ahoy(&["send", "--from-claude", "--format", "gemini"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = ahoy(&["send", "--from-codex", "--from-gemini", "{}"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = ahoy(&["send", "--summary-only", "hi"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_send_dry_run_prints_without_delivering() {
+    let home = tempfile::TempDir::new().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_ahoy"))
+        .args([
+            "send",
+            "--dry-run",
+            "--title",
+            "Deploy",
+            "--urgency",
+            "critical",
+            "Needs approval",
+        ])
+        .env("HOME", home.path())
+        .env_remove("XDG_DATA_HOME")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let notification: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notification["title"], "Deploy");
+    assert_eq!(notification["body"], "Needs approval");
+    assert_eq!(notification["urgency"], "critical");
+    // Delivered notifications are logged; a dry run never gets that far
+    assert!(!home.path().join(".ahoy/history.jsonl").exists());
+    assert!(!home.path().join(".local/share/ahoy/history.jsonl").exists());
+}
+
+/// Run ahoy in a fresh home whose config.toml has `config`
+fn ahoy_with_config(config: &str, args: &[&str]) -> Output {
+    let home = tempfile::TempDir::new().unwrap();
+    let config_dir = if cfg!(target_os = "linux") {
+        home.path().join(".config/ahoy")
+    } else {
+        home.path().join(".ahoy")
+    };
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), config).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_ahoy"))
+        .args(args)
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("XDG_STATE_HOME")
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_unknown_config_keys_warn() {
+    let output = ahoy_with_config(
+        "default_tilte = \"typo\"\ndefault_title = \"Build bot\"\n",
+        &["send", "--dry-run", "hi"],
+    );
+
+    assert!(output.status.success());
+    let notification: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notification["title"], "Build bot");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Ignoring unknown keys"), "{}", stderr);
+    assert!(stderr.contains("default_tilte"), "{}", stderr);
+}
+
+#[test]
+fn test_invalid_log_level_falls_back_to_info() {
+    let output = ahoy_with_config(
+        "log_level = \"ahoy=loudest\"\n",
+        &["send", "--dry-run", "hi"],
+    );
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid log_level in config"), "{}", stderr);
+}
+
+#[test]
+fn test_malformed_config_only_breaks_send() {
+    let history = ahoy_with_config("default_title = \n", &["history"]);
+    assert!(history.status.success());
+
+    let send = ahoy_with_config("default_title = \n", &["send", "--dry-run", "hi"]);
+    assert_eq!(send.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&send.stderr).contains("Failed to parse"));
+}
+
+#[test]
+fn test_doctor_reports_malformed_config() {
+    let output = ahoy_with_config("default_title = \n", &["doctor"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let config_line = stdout
+        .lines()
+        .find(|line| line.contains("Config:"))
+        .unwrap();
+    assert!(config_line.starts_with("[FAIL]"), "{}", stdout);
+    assert!(config_line.contains("Failed to parse"), "{}", stdout);
+}
+
+#[test]
+fn test_json_stdin_conflicts_with_from_claude() {
+    let output = ahoy(&["send", "--json-stdin", "--from-claude"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--json-stdin"));
+}
+
+#[test]
+fn test_json_stdin_dry_run() {
+    let home = tempfile::TempDir::new().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ahoy"))
+        .args(["send", "--json-stdin", "--dry-run"])
+        .env("HOME", home.path())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(br#"{"title": "CI", "body": "Build failed", "metadata": {"job": 42}}"#)
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    let notification: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notification["title"], "CI");
+    assert_eq!(notification["metadata"]["job"], 42);
+}
+
+#[test]
+fn test_json_stdin_batch_isolates_failures() {
+    let home = tempfile::TempDir::new().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ahoy"))
+        .args(["send", "--json-stdin", "--dry-run"])
+        .env("HOME", home.path())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            br#"[{"title": "A", "body": "one"}, {"title": 7}, {"title": "C", "body": "three"}]"#,
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    let bodies: Vec<Value> = serde_json::Deserializer::from_slice(&output.stdout)
+        .into_iter::<Value>()
+        .map(|n| n.unwrap()["body"].clone())
+        .collect();
+    assert_eq!(bodies, ["one", "three"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("1 of 3 notifications failed"));
+}
@@ -0,0 +1,113 @@
+use ahoy::install::codex;
+use ahoy::install::setup::InstallOptions;
+use serial_test::serial;
+use std::fs;
+use tempfile::TempDir;
+
+// Helper to set up a test home directory
+fn setup_test_env() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    unsafe {
+        std::env::set_var("AHOY_TEST_HOME", temp_dir.path());
+    }
+    temp_dir
+}
+
+// Helper to write config.toml
+fn write_config(temp_dir: &TempDir, content: &str) {
+    let codex_dir = temp_dir.path().join(".codex");
+    fs::create_dir_all(&codex_dir).unwrap();
+    fs::write(codex_dir.join("config.toml"), content).unwrap();
+}
+
+// Helper to read config.toml
+fn read_config(temp_dir: &TempDir) -> String {
+    fs::read_to_string(temp_dir.path().join(".codex/config.toml")).unwrap()
+}
+
+#[test]
+#[serial]
+fn test_install_sets_notify_command() {
+    let temp_dir = setup_test_env();
+
+    codex::install().unwrap();
+
+    let config = read_config(&temp_dir);
+    assert!(config.starts_with("notify = ["));
+    assert!(config.contains(r#""send", "--agent", "codex", "--from-codex"]"#));
+    assert!(codex::is_installed());
+}
+
+#[test]
+#[serial]
+fn test_install_keeps_comments_and_puts_notify_before_tables() {
+    let temp_dir = setup_test_env();
+    write_config(
+        &temp_dir,
+        r#"# my codex settings
+model = "o3"
+
+[profiles.fast]
+model = "o4-mini"
+"#,
+    );
+
+    codex::install().unwrap();
+
+    let config = read_config(&temp_dir);
+    assert!(config.contains("# my codex settings"));
+    let notify = config.find("notify = ").unwrap();
+    let table = config.find("[profiles.fast]").unwrap();
+    assert!(notify < table);
+}
+
+#[test]
+#[serial]
+fn test_install_refuses_foreign_notify() {
+    let temp_dir = setup_test_env();
+    write_config(&temp_dir, "notify = [\"notify-send\", \"Codex\"]\n");
+
+    assert!(
+        codex::install_with(&InstallOptions {
+            replace: true,
+            ..Default::default()
+        })
+        .is_err()
+    );
+    assert_eq!(
+        read_config(&temp_dir),
+        "notify = [\"notify-send\", \"Codex\"]\n"
+    );
+}
+
+#[test]
+#[serial]
+fn test_install_replace_upgrades_old_command() {
+    let temp_dir = setup_test_env();
+    write_config(
+        &temp_dir,
+        "model = \"o3\"\nnotify = [\"/old/bin/ahoy\", \"send\", \"Done\"]\n",
+    );
+
+    codex::install_with(&InstallOptions {
+        replace: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let config = read_config(&temp_dir);
+    assert!(!config.contains("/old/bin/ahoy"));
+    assert!(config.contains("--from-codex"));
+    assert!(config.contains("model = \"o3\""));
+}
+
+#[test]
+#[serial]
+fn test_uninstall_leaves_foreign_notify() {
+    let temp_dir = setup_test_env();
+    write_config(&temp_dir, "notify = [\"notify-send\", \"Codex\"]\n");
+
+    codex::uninstall().unwrap();
+
+    assert!(read_config(&temp_dir).contains("notify-send"));
+}
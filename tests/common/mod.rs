@@ -0,0 +1,117 @@
+// Shared harness for exercising every agent's install/uninstall/status flow
+use ahoy::install::{claude, codex, cursor, gemini};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// An agent whose hooks ahoy can install, with the hooks it needs for testing
+pub struct AgentCase {
+    pub name: &'static str,
+    /// Config file path relative to the test home directory
+    pub config_path: &'static str,
+    pub install: fn() -> anyhow::Result<()>,
+    pub uninstall: fn() -> anyhow::Result<()>,
+    pub is_installed: fn() -> bool,
+    /// Config content with an unrelated user hook that must survive install/uninstall
+    pub foreign_config: &'static str,
+    /// String identifying the unrelated hook in the written config
+    pub foreign_marker: &'static str,
+}
+
+pub fn agents() -> Vec<AgentCase> {
+    vec![
+        AgentCase {
+            name: "claude",
+            config_path: ".claude/settings.json",
+            install: claude::install,
+            uninstall: claude::uninstall,
+            is_installed: claude::is_installed,
+            foreign_config: r#"{
+  "hooks": {
+    "Stop": [
+      {
+        "matcher": "",
+        "hooks": [{"type": "command", "command": "/other/tool --flag", "timeout": 5000}]
+      }
+    ]
+  }
+}"#,
+            foreign_marker: "/other/tool --flag",
+        },
+        AgentCase {
+            name: "codex",
+            config_path: ".codex/config.toml",
+            install: codex::install,
+            uninstall: codex::uninstall,
+            is_installed: codex::is_installed,
+            foreign_config: r#"model = "o3"
+
+[mcp_servers.docs]
+command = "/other/tool --flag"
+"#,
+            foreign_marker: "/other/tool --flag",
+        },
+        AgentCase {
+            name: "gemini",
+            config_path: ".gemini/settings.json",
+            install: gemini::install,
+            uninstall: gemini::uninstall,
+            is_installed: gemini::is_installed,
+            foreign_config: r#"{
+  "theme": "Default",
+  "hooks": {
+    "AfterAgent": [
+      {
+        "matcher": "*",
+        "hooks": [{"type": "command", "command": "/other/tool --flag", "timeout": 5000}]
+      }
+    ]
+  }
+}"#,
+            foreign_marker: "/other/tool --flag",
+        },
+        AgentCase {
+            name: "cursor",
+            config_path: ".cursor/hooks.json",
+            install: cursor::install,
+            uninstall: cursor::uninstall,
+            is_installed: cursor::is_installed,
+            foreign_config: r#"{
+  "version": 1,
+  "hooks": {
+    "stop": [{"command": "/other/tool --flag"}]
+  }
+}"#,
+            foreign_marker: "/other/tool --flag",
+        },
+    ]
+}
+
+/// A temp home directory that AHOY_TEST_HOME points at for one agent case
+pub struct TestHome {
+    pub dir: TempDir,
+}
+
+impl TestHome {
+    pub fn new() -> Self {
+        let dir = TempDir::new().unwrap();
+        unsafe {
+            std::env::set_var("AHOY_TEST_HOME", dir.path());
+        }
+        Self { dir }
+    }
+
+    pub fn config_file(&self, agent: &AgentCase) -> PathBuf {
+        self.dir.path().join(agent.config_path)
+    }
+
+    pub fn write_config(&self, agent: &AgentCase, content: &str) {
+        let path = self.config_file(agent);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    pub fn read_config(&self, agent: &AgentCase) -> String {
+        fs::read_to_string(self.config_file(agent)).unwrap()
+    }
+}
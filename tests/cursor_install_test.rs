@@ -0,0 +1,138 @@
+use ahoy::install::cursor;
+use ahoy::install::setup::InstallOptions;
+use serde_json::{Value, json};
+use serial_test::serial;
+use std::fs;
+use tempfile::TempDir;
+
+// Helper to set up a test home directory
+fn setup_test_env() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    unsafe {
+        std::env::set_var("AHOY_TEST_HOME", temp_dir.path());
+    }
+    temp_dir
+}
+
+// Helper to write hooks.json
+fn write_hooks(temp_dir: &TempDir, content: Value) {
+    let cursor_dir = temp_dir.path().join(".cursor");
+    fs::create_dir_all(&cursor_dir).unwrap();
+    fs::write(
+        cursor_dir.join("hooks.json"),
+        serde_json::to_string_pretty(&content).unwrap(),
+    )
+    .unwrap();
+}
+
+// Helper to read hooks.json
+fn read_hooks(temp_dir: &TempDir) -> Value {
+    let content = fs::read_to_string(temp_dir.path().join(".cursor/hooks.json")).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+#[test]
+#[serial]
+fn test_install_creates_versioned_hooks_file() {
+    let temp_dir = setup_test_env();
+
+    cursor::install().unwrap();
+
+    let hooks = read_hooks(&temp_dir);
+    assert_eq!(hooks["version"], 1);
+
+    let stop_hooks = hooks["hooks"]["stop"].as_array().unwrap();
+    assert_eq!(stop_hooks.len(), 1);
+
+    let command = stop_hooks[0]["command"].as_str().unwrap();
+    assert!(command.contains("ahoy"));
+    assert!(command.contains("--agent cursor"));
+}
+
+#[test]
+#[serial]
+fn test_install_keeps_other_hook_events() {
+    let temp_dir = setup_test_env();
+    write_hooks(
+        &temp_dir,
+        json!({
+            "version": 1,
+            "hooks": {
+                "beforeShellExecution": [{"command": "./audit.sh"}]
+            }
+        }),
+    );
+
+    cursor::install().unwrap();
+
+    let hooks = read_hooks(&temp_dir);
+    assert_eq!(
+        hooks["hooks"]["beforeShellExecution"][0]["command"],
+        "./audit.sh"
+    );
+    assert!(cursor::is_installed());
+}
+
+#[test]
+#[serial]
+fn test_is_installed_false_empty_hooks() {
+    let temp_dir = setup_test_env();
+    write_hooks(&temp_dir, json!({"version": 1}));
+
+    assert!(!cursor::is_installed());
+}
+
+#[test]
+#[serial]
+fn test_install_replace_upgrades_old_hooks() {
+    let temp_dir = setup_test_env();
+    write_hooks(
+        &temp_dir,
+        json!({
+            "version": 1,
+            "hooks": {
+                "stop": [
+                    {"command": "/old/bin/ahoy send 'Done'"},
+                    {"command": "/other/tool --flag"}
+                ]
+            }
+        }),
+    );
+
+    cursor::install_with(&InstallOptions {
+        replace: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let hooks = read_hooks(&temp_dir);
+    let stop_hooks = hooks["hooks"]["stop"].as_array().unwrap();
+    assert_eq!(stop_hooks.len(), 2);
+    assert_eq!(stop_hooks[0]["command"], "/other/tool --flag");
+    assert!(
+        stop_hooks[1]["command"]
+            .as_str()
+            .unwrap()
+            .contains("--agent cursor")
+    );
+}
+
+#[test]
+#[serial]
+fn test_install_without_replace_keeps_old_hooks() {
+    let temp_dir = setup_test_env();
+    write_hooks(
+        &temp_dir,
+        json!({
+            "version": 1,
+            "hooks": {"stop": [{"command": "/old/bin/ahoy send 'Done'"}]}
+        }),
+    );
+
+    cursor::install().unwrap();
+
+    let hooks = read_hooks(&temp_dir);
+    let stop_hooks = hooks["hooks"]["stop"].as_array().unwrap();
+    assert_eq!(stop_hooks.len(), 1);
+    assert_eq!(stop_hooks[0]["command"], "/old/bin/ahoy send 'Done'");
+}
@@ -0,0 +1,141 @@
+use ahoy::install::gemini;
+use ahoy::install::setup::InstallOptions;
+use serde_json::{Value, json};
+use serial_test::serial;
+use std::fs;
+use tempfile::TempDir;
+
+// Helper to set up a test home directory
+fn setup_test_env() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    unsafe {
+        std::env::set_var("AHOY_TEST_HOME", temp_dir.path());
+    }
+    temp_dir
+}
+
+// Helper to write settings.json
+fn write_settings(temp_dir: &TempDir, content: Value) {
+    let gemini_dir = temp_dir.path().join(".gemini");
+    fs::create_dir_all(&gemini_dir).unwrap();
+    fs::write(
+        gemini_dir.join("settings.json"),
+        serde_json::to_string_pretty(&content).unwrap(),
+    )
+    .unwrap();
+}
+
+// Helper to read settings.json
+fn read_settings(temp_dir: &TempDir) -> Value {
+    let content = fs::read_to_string(temp_dir.path().join(".gemini/settings.json")).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+fn command(hook: &Value) -> &str {
+    hook["hooks"][0]["command"].as_str().unwrap()
+}
+
+#[test]
+#[serial]
+fn test_install_adds_after_agent_and_notification_hooks() {
+    let temp_dir = setup_test_env();
+    write_settings(&temp_dir, json!({}));
+
+    gemini::install().unwrap();
+
+    let settings = read_settings(&temp_dir);
+    for event in ["AfterAgent", "Notification"] {
+        let hooks = settings["hooks"][event].as_array().unwrap();
+        assert_eq!(hooks.len(), 1, "{}", event);
+        assert!(command(&hooks[0]).contains("--agent gemini"), "{}", event);
+    }
+}
+
+#[test]
+#[serial]
+fn test_install_idempotent() {
+    let temp_dir = setup_test_env();
+
+    gemini::install().unwrap();
+    gemini::install().unwrap();
+
+    let settings = read_settings(&temp_dir);
+    assert_eq!(settings["hooks"]["AfterAgent"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        settings["hooks"]["Notification"].as_array().unwrap().len(),
+        1
+    );
+}
+
+#[test]
+#[serial]
+fn test_install_keeps_other_settings() {
+    let temp_dir = setup_test_env();
+    write_settings(
+        &temp_dir,
+        json!({
+            "theme": "GitHub",
+            "hooks": {"BeforeTool": [{"matcher": "run_shell_command", "hooks": []}]}
+        }),
+    );
+
+    gemini::install().unwrap();
+
+    let settings = read_settings(&temp_dir);
+    assert_eq!(settings["theme"], "GitHub");
+    assert!(settings["hooks"]["BeforeTool"].is_array());
+}
+
+#[test]
+#[serial]
+fn test_uninstall_preserves_other_notification_hooks() {
+    let temp_dir = setup_test_env();
+    write_settings(
+        &temp_dir,
+        json!({
+            "hooks": {
+                "Notification": [{
+                    "matcher": "*",
+                    "hooks": [{"type": "command", "command": "/usr/bin/say done"}]
+                }]
+            }
+        }),
+    );
+
+    gemini::install().unwrap();
+    gemini::uninstall().unwrap();
+
+    let settings = read_settings(&temp_dir);
+    let notification_hooks = settings["hooks"]["Notification"].as_array().unwrap();
+    assert_eq!(notification_hooks.len(), 1);
+    assert_eq!(command(&notification_hooks[0]), "/usr/bin/say done");
+    assert!(!gemini::is_installed());
+}
+
+#[test]
+#[serial]
+fn test_install_replace_upgrades_old_hooks() {
+    let temp_dir = setup_test_env();
+    write_settings(
+        &temp_dir,
+        json!({
+            "hooks": {
+                "AfterAgent": [{
+                    "matcher": "*",
+                    "hooks": [{"type": "command", "command": "/old/bin/ahoy send 'Done'"}]
+                }]
+            }
+        }),
+    );
+
+    gemini::install_with(&InstallOptions {
+        replace: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let settings = read_settings(&temp_dir);
+    let after_agent = settings["hooks"]["AfterAgent"].as_array().unwrap();
+    assert_eq!(after_agent.len(), 1);
+    assert!(command(&after_agent[0]).contains("--agent gemini"));
+}
@@ -31,6 +31,18 @@ else
     echo "Ahoy is not installed at $AHOY_HOME"
 fi
 
+# On Linux, config, history and state live in the XDG base directories
+if [[ "$OSTYPE" == "linux"* ]]; then
+    for dir in "${XDG_CONFIG_HOME:-$HOME/.config}/ahoy" \
+               "${XDG_DATA_HOME:-$HOME/.local/share}/ahoy" \
+               "${XDG_STATE_HOME:-$HOME/.local/state}/ahoy"; do
+        if [ -d "$dir" ]; then
+            echo "Removing $dir..."
+            rm -rf "$dir"
+        fi
+    done
+fi
+
 echo ""
 echo "Note: You may want to remove this line from your shell config:"
 echo "  export PATH=\"\$HOME/.ahoy/bin:\$PATH\""